@@ -0,0 +1,104 @@
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{SecondsFormat, Utc};
+use serde_json::json;
+
+use crate::redact::{REDACTED, redact_tokens};
+
+/// Flags of `gh secret set` whose value is the secret itself
+const SECRET_VALUE_FLAGS: [&str; 2] = ["--body", "-b"];
+
+/// Appends a newline-delimited JSON record for every executed gh command
+pub struct AuditLogger {
+    file: Mutex<File>,
+}
+
+impl AuditLogger {
+    /// Open the audit log for appending, creating it if needed
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self { file: Mutex::new(file) })
+    }
+
+    /// Record one command; failures to write are logged but never fail the command
+    pub fn record(&self, args: &[String], success: bool, exit_code: i32, duration: Duration) {
+        let record = json!({
+            "timestamp": Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+            "args": redact_args(args),
+            "success": success,
+            "exit_code": exit_code,
+            "duration_ms": duration.as_millis() as u64,
+        });
+        let mut file = self.file.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Err(e) = writeln!(file, "{}", record) {
+            tracing::warn!("Failed to write audit record: {}", e);
+        }
+    }
+}
+
+/// Scrub tokens from all arguments and secret values passed on the command line
+fn redact_args(args: &[String]) -> Vec<String> {
+    let is_secret = args.first().is_some_and(|command| command == "secret");
+    let mut redacted = Vec::with_capacity(args.len());
+    let mut redact_next = false;
+    for arg in args {
+        if redact_next {
+            redacted.push(REDACTED.to_string());
+            redact_next = false;
+            continue;
+        }
+        if is_secret {
+            if SECRET_VALUE_FLAGS.contains(&arg.as_str()) {
+                redact_next = true;
+            } else if let Some((flag, _)) = arg.split_once('=').filter(|(flag, _)| SECRET_VALUE_FLAGS.contains(flag)) {
+                redacted.push(format!("{}={}", flag, REDACTED));
+                continue;
+            }
+        }
+        redacted.push(redact_tokens(arg));
+    }
+    redacted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn redact_args_hides_long_body_flag() {
+        let redacted = redact_args(&args(&["secret", "set", "NPM_TOKEN", "--body", "hunter2", "--repo", "octo/repo"]));
+        assert_eq!(redacted, args(&["secret", "set", "NPM_TOKEN", "--body", REDACTED, "--repo", "octo/repo"]));
+    }
+
+    #[test]
+    fn redact_args_hides_short_body_flag() {
+        let redacted = redact_args(&args(&["secret", "set", "NPM_TOKEN", "-b", "hunter2"]));
+        assert_eq!(redacted, args(&["secret", "set", "NPM_TOKEN", "-b", REDACTED]));
+    }
+
+    #[test]
+    fn redact_args_hides_inline_body_flag() {
+        let redacted = redact_args(&args(&["secret", "set", "NPM_TOKEN", "--body=hunter2"]));
+        assert_eq!(redacted, args(&["secret", "set", "NPM_TOKEN", &format!("--body={}", REDACTED)]));
+    }
+
+    #[test]
+    fn redact_args_hides_tokens_in_any_command() {
+        let redacted = redact_args(&args(&["api", "-H", "Authorization: token ghp_abc123DEF", "user"]));
+        assert_eq!(redacted, args(&["api", "-H", &format!("Authorization: token {}", REDACTED), "user"]));
+    }
+
+    #[test]
+    fn redact_args_keeps_body_of_other_commands() {
+        let redacted = redact_args(&args(&["issue", "create", "--body", "Steps to reproduce"]));
+        assert_eq!(redacted, args(&["issue", "create", "--body", "Steps to reproduce"]));
+    }
+}
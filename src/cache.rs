@@ -0,0 +1,78 @@
+use std::hash::Hash;
+use std::num::NonZeroUsize;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use lru::LruCache;
+
+/// LRU cache whose entries expire after a fixed time to live
+pub struct Cache<K: Hash + Eq, V: Clone> {
+    entries: Mutex<LruCache<K, (Instant, V)>>,
+    ttl: Duration,
+}
+
+impl<K: Hash + Eq, V: Clone> Cache<K, V> {
+    pub fn new(capacity: NonZeroUsize, ttl: Duration) -> Self {
+        Self {
+            entries: Mutex::new(LruCache::new(capacity)),
+            ttl,
+        }
+    }
+
+    /// Get a value if present and not yet expired
+    pub fn get(&self, key: &K) -> Option<V> {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        match entries.get(key) {
+            Some((stored_at, value)) if stored_at.elapsed() < self.ttl => Some(value.clone()),
+            Some(_) => {
+                entries.pop(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    pub fn insert(&self, key: K, value: V) {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.put(key, (Instant::now(), value));
+    }
+
+    pub fn clear(&self) {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache(ttl: Duration) -> Cache<&'static str, u32> {
+        Cache::new(NonZeroUsize::new(4).unwrap(), ttl)
+    }
+
+    #[test]
+    fn get_returns_fresh_entries() {
+        let cache = cache(Duration::from_secs(60));
+        cache.insert("repos", 1);
+        assert_eq!(cache.get(&"repos"), Some(1));
+    }
+
+    #[test]
+    fn get_drops_expired_entries() {
+        let cache = cache(Duration::from_millis(10));
+        cache.insert("repos", 1);
+        std::thread::sleep(Duration::from_millis(20));
+        assert_eq!(cache.get(&"repos"), None);
+    }
+
+    #[test]
+    fn clear_drops_every_entry() {
+        let cache = cache(Duration::from_secs(60));
+        cache.insert("repos", 1);
+        cache.insert("labels", 2);
+        cache.clear();
+        assert_eq!(cache.get(&"repos"), None);
+        assert_eq!(cache.get(&"labels"), None);
+    }
+}
@@ -0,0 +1,86 @@
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Server configuration, read from `GH_MCP_*` environment variables
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// Maximum number of cached read-only command results, 0 disables caching
+    pub cache_capacity: usize,
+    /// How long a cached result stays valid
+    pub cache_ttl: Duration,
+    /// Log gh commands instead of executing them
+    pub dry_run: bool,
+    /// File that receives a JSON record of every executed gh command
+    pub audit_log: Option<PathBuf>,
+    /// Attempts made for a read-only gh command failing with a transient error
+    pub retry_attempts: u32,
+    /// Delay before the first retry, doubled for each further one
+    pub retry_base_delay: Duration,
+    /// How long running tool calls may take to finish after a shutdown signal
+    pub shutdown_grace: Duration,
+    /// Allow running arbitrary commands inside codespaces
+    pub allow_codespace_exec: bool,
+    /// GitHub CLI executable, a name looked up on PATH or a path
+    pub gh_binary: String,
+    /// Maximum number of gh processes running at once
+    pub max_concurrent: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            cache_capacity: 128,
+            cache_ttl: Duration::from_secs(60),
+            dry_run: false,
+            audit_log: None,
+            retry_attempts: 3,
+            retry_base_delay: Duration::from_millis(500),
+            shutdown_grace: Duration::from_secs(30),
+            allow_codespace_exec: false,
+            gh_binary: "gh".to_string(),
+            max_concurrent: 8,
+        }
+    }
+}
+
+impl Config {
+    /// Build configuration from the environment, falling back to defaults
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            cache_capacity: env_or("GH_MCP_CACHE_CAPACITY", defaults.cache_capacity),
+            cache_ttl: Duration::from_secs(env_or("GH_MCP_CACHE_TTL_SECS", defaults.cache_ttl.as_secs())),
+            dry_run: env_or("GH_MCP_DRY_RUN", defaults.dry_run),
+            audit_log: std::env::var_os("GH_MCP_AUDIT_LOG")
+                .filter(|path| !path.is_empty())
+                .map(PathBuf::from),
+            retry_attempts: env_or("GH_MCP_RETRY_ATTEMPTS", defaults.retry_attempts),
+            retry_base_delay: Duration::from_millis(env_or(
+                "GH_MCP_RETRY_BASE_DELAY_MS",
+                defaults.retry_base_delay.as_millis() as u64,
+            )),
+            shutdown_grace: Duration::from_secs(env_or(
+                "GH_MCP_SHUTDOWN_GRACE_SECS",
+                defaults.shutdown_grace.as_secs(),
+            )),
+            allow_codespace_exec: env_or("GH_MCP_ALLOW_CODESPACE_EXEC", defaults.allow_codespace_exec),
+            gh_binary: std::env::var("GH_MCP_BINARY")
+                .ok()
+                .filter(|binary| !binary.trim().is_empty())
+                .unwrap_or(defaults.gh_binary),
+            max_concurrent: env_or("GH_MCP_MAX_CONCURRENT", defaults.max_concurrent),
+        }
+    }
+}
+
+/// Parse an environment variable, warning and using the default when it is invalid
+fn env_or<T: FromStr>(name: &str, default: T) -> T {
+    match std::env::var(name) {
+        Ok(value) => value.trim().parse().unwrap_or_else(|_| {
+            tracing::warn!("Ignoring invalid value {:?} for {}", value, name);
+            default
+        }),
+        Err(_) => default,
+    }
+}
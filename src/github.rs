@@ -1,21 +1,38 @@
+use std::collections::HashMap;
+use std::num::NonZeroUsize;
 use std::sync::Arc;
-use std::process::Command;
+use std::process::Stdio;
+use std::time::{Duration, Instant};
 
 use rmcp::{
     Error as McpError, RoleServer, ServerHandler, model::*, 
-    service::RequestContext, tool,
+    handler::server::tool::ToolCallContext, service::RequestContext, tool,
 };
 use serde::{Deserialize, Serialize};
-use serde_json::json;
-use tokio::sync::Mutex;
-use anyhow::Result;
+use serde_json::{json, Value};
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tokio::sync::{Mutex, OwnedRwLockWriteGuard, RwLock, Semaphore};
+use tokio::task::JoinSet;
+use tracing::Instrument;
+use uuid::Uuid;
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate, SecondsFormat, Utc};
+
+use crate::audit::AuditLogger;
+use crate::cache::Cache;
+use crate::config::Config;
+use crate::metrics::Metrics;
+use crate::redact::{REDACTED, register_secret};
 
 /// GitHub CLI command result
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CommandResult {
     pub success: bool,
+    pub exit_code: i32,
     pub output: String,
     pub error: Option<String>,
+    pub stderr: Option<String>,
 }
 
 /// Repository info request parameters
@@ -25,6 +42,484 @@ pub struct RepoParam {
     pub repo: String,
 }
 
+/// HTTP method of a raw API request
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum ApiMethod {
+    Get,
+    Post,
+    Put,
+    Patch,
+    Delete,
+}
+
+impl ApiMethod {
+    fn as_str(self) -> &'static str {
+        match self {
+            ApiMethod::Get => "GET",
+            ApiMethod::Post => "POST",
+            ApiMethod::Put => "PUT",
+            ApiMethod::Patch => "PATCH",
+            ApiMethod::Delete => "DELETE",
+        }
+    }
+}
+
+/// Raw API request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GhApiParam {
+    #[schemars(description = "API path relative to the API root, e.g. repos/octo/app/branches or graphql; not a full URL")]
+    pub path: String,
+    #[schemars(description = "HTTP method, defaults to GET")]
+    pub method: Option<ApiMethod>,
+    #[schemars(description = "Query string parameters, URL-encoded and appended to the path")]
+    #[serde(default)]
+    pub query: HashMap<String, String>,
+    #[schemars(description = "Request fields: query parameters for GET, otherwise a JSON body. Booleans, numbers and null keep their JSON type; use raw_body for nested objects or arrays")]
+    #[serde(default)]
+    pub fields: HashMap<String, Value>,
+    #[schemars(description = "Raw request body sent as is, e.g. a JSON document; cannot be combined with fields")]
+    pub raw_body: Option<String>,
+    #[schemars(description = "Extra request headers by name")]
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    #[schemars(description = "Accept header, e.g. application/vnd.github.raw+json or a preview media type")]
+    pub accept: Option<String>,
+    #[schemars(description = "Follow all pages of a GET list endpoint and merge them into one items array")]
+    #[serde(default)]
+    pub paginate: bool,
+    #[schemars(description = "With paginate, maximum number of items to return, defaults to 1000")]
+    pub max_items: Option<u32>,
+}
+
+/// GraphQL request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GraphqlParam {
+    #[schemars(description = "GraphQL query or mutation document, declaring its variables, e.g. query($login: String!) { user(login: $login) { name } }")]
+    pub query: String,
+    #[schemars(description = "Variables by name with their JSON values; numbers, booleans, null, and nested objects or arrays for input types are sent as typed JSON, not as strings")]
+    #[serde(default)]
+    pub variables: serde_json::Map<String, Value>,
+}
+
+/// Rate limit request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct RateLimitParam {
+    #[schemars(description = "Always ask the API instead of answering from the quota seen on recent responses")]
+    #[serde(default)]
+    pub refresh: bool,
+}
+
+/// Check collaborator request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct CheckCollaboratorParam {
+    pub owner: String,
+    pub repo: String,
+    #[schemars(description = "User login to check")]
+    pub username: String,
+}
+
+/// Add deploy key request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct AddDeployKeyParam {
+    pub owner: String,
+    pub repo: String,
+    #[schemars(description = "Name of the key shown in the repository settings")]
+    pub title: String,
+    #[schemars(description = "SSH public key, e.g. the contents of id_ed25519.pub")]
+    pub key: String,
+    #[schemars(description = "Whether the key can only read the repository, defaults to true")]
+    pub read_only: Option<bool>,
+}
+
+/// Add account key request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct AddKeyParam {
+    #[schemars(description = "Name of the key shown in the account settings")]
+    pub title: String,
+    #[schemars(description = "Public key: an SSH public key such as the contents of id_ed25519.pub, or an ASCII-armored GPG public key block")]
+    pub key: String,
+}
+
+/// Delete SSH key request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct DeleteSshKeyParam {
+    #[schemars(description = "SSH key ID, from list_ssh_keys")]
+    pub id: u64,
+    #[schemars(description = "Must be true to delete the key")]
+    #[serde(default)]
+    pub confirm: bool,
+}
+
+/// Delete GPG key request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct DeleteGpgKeyParam {
+    #[schemars(description = "GPG key ID, the hexadecimal key_id from list_gpg_keys")]
+    pub key_id: String,
+    #[schemars(description = "Must be true to delete the key")]
+    #[serde(default)]
+    pub confirm: bool,
+}
+
+/// Payload format of webhook deliveries
+#[derive(Debug, Clone, Copy, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookContentType {
+    Json,
+    Form,
+}
+
+impl WebhookContentType {
+    fn as_str(self) -> &'static str {
+        match self {
+            WebhookContentType::Json => "json",
+            WebhookContentType::Form => "form",
+        }
+    }
+}
+
+/// Create webhook request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct CreateWebhookParam {
+    pub owner: String,
+    pub repo: String,
+    #[schemars(description = "HTTPS URL receiving the deliveries")]
+    pub url: String,
+    #[schemars(description = "Events triggering a delivery, e.g. push, pull_request or * for all")]
+    pub events: Vec<String>,
+    #[schemars(description = "Shared secret used to sign deliveries; never logged or returned")]
+    pub secret: Option<String>,
+    #[schemars(description = "Payload format, defaults to json")]
+    pub content_type: Option<WebhookContentType>,
+    #[schemars(description = "Whether deliveries are sent right away, defaults to true; false registers the webhook disabled")]
+    pub active: Option<bool>,
+}
+
+/// Delete webhook request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct DeleteWebhookParam {
+    pub owner: String,
+    pub repo: String,
+    #[schemars(description = "Webhook ID, from list_webhooks")]
+    pub hook_id: u64,
+    #[schemars(description = "Must be true to delete the webhook")]
+    #[serde(default)]
+    pub confirm: bool,
+}
+
+/// Release diff request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ReleaseDiffParam {
+    pub owner: String,
+    pub repo: String,
+    #[schemars(description = "Tag of the earlier release")]
+    pub from_tag: String,
+    #[schemars(description = "Tag of the later release")]
+    pub to_tag: String,
+}
+
+/// Generate release notes request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GenerateReleaseNotesParam {
+    pub owner: String,
+    pub repo: String,
+    #[schemars(description = "Tag of the release to describe; it does not need to exist yet")]
+    pub tag: String,
+    #[schemars(description = "Tag of the previous release, defaults to the latest release before tag")]
+    pub previous_tag: Option<String>,
+}
+
+/// Issue request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct IssueParam {
+    pub owner: String,
+    pub repo: String,
+    pub number: u64,
+}
+
+/// Issue timeline request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ListIssueTimelineParam {
+    pub owner: String,
+    pub repo: String,
+    pub number: u64,
+}
+
+/// Reason shown when an issue conversation is locked
+#[derive(Debug, Clone, Copy, Deserialize, schemars::JsonSchema)]
+pub enum LockReason {
+    #[serde(rename = "off-topic")]
+    OffTopic,
+    #[serde(rename = "too heated")]
+    TooHeated,
+    #[serde(rename = "resolved")]
+    Resolved,
+    #[serde(rename = "spam")]
+    Spam,
+}
+
+impl LockReason {
+    fn as_str(self) -> &'static str {
+        match self {
+            LockReason::OffTopic => "off-topic",
+            LockReason::TooHeated => "too heated",
+            LockReason::Resolved => "resolved",
+            LockReason::Spam => "spam",
+        }
+    }
+}
+
+/// Lock issue request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct LockIssueParam {
+    pub owner: String,
+    pub repo: String,
+    pub number: u64,
+    pub lock_reason: Option<LockReason>,
+}
+
+/// Pin issue request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct PinIssueParam {
+    pub owner: String,
+    pub repo: String,
+    pub number: u64,
+}
+
+/// Transfer issue request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct TransferIssueParam {
+    pub owner: String,
+    pub repo: String,
+    pub number: u64,
+    pub target_owner: String,
+    pub target_repo: String,
+}
+
+/// Find similar issues request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct FindSimilarIssuesParam {
+    pub owner: String,
+    pub repo: String,
+    #[schemars(description = "Title of the issue about to be filed")]
+    pub title: String,
+    #[schemars(description = "Maximum number of matches, defaults to 5")]
+    pub limit: Option<u32>,
+}
+
+/// Bulk label request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct BulkLabelParam {
+    pub owner: String,
+    pub repo: String,
+    #[schemars(description = "Issue or pull request numbers to edit")]
+    pub numbers: Vec<u64>,
+    #[schemars(description = "Labels to add to every issue")]
+    #[serde(default)]
+    pub add_labels: Vec<String>,
+    #[schemars(description = "Labels to remove from every issue")]
+    pub remove_labels: Option<Vec<String>>,
+}
+
+/// Notification subscription to a repository
+#[derive(Debug, Clone, Copy, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SubscriptionMode {
+    /// Notify about all activity
+    Watch,
+    /// Never notify, even when participating or mentioned
+    Ignore,
+    /// Back to the default: notify only when participating or mentioned
+    Unwatch,
+}
+
+/// Set repository subscription request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct SetRepoSubscriptionParam {
+    pub owner: String,
+    pub repo: String,
+    pub mode: SubscriptionMode,
+}
+
+/// List watched repositories request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ListWatchedParam {
+    #[serde(flatten)]
+    pub page: PageParam,
+}
+
+/// List starred repositories request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ListStarredParam {
+    #[schemars(description = "User whose stars to list, defaults to the current user")]
+    pub username: Option<String>,
+    #[schemars(description = "Only repositories whose primary language matches, case-insensitive; applied to each fetched page")]
+    pub language: Option<String>,
+    #[serde(flatten)]
+    pub page: PageParam,
+}
+
+/// List followers or followed users request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ListFollowsParam {
+    #[schemars(description = "User whose follows to list, defaults to the current user")]
+    pub username: Option<String>,
+    #[serde(flatten)]
+    pub page: PageParam,
+}
+
+/// User login request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct LoginParam {
+    #[schemars(description = "User login")]
+    pub login: String,
+}
+
+/// Is following request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct IsFollowingParam {
+    #[schemars(description = "User who may be followed")]
+    pub login: String,
+    #[schemars(description = "User who may follow login, defaults to the current user")]
+    pub username: Option<String>,
+}
+
+/// List notifications request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ListNotificationsParam {
+    #[schemars(description = "Include notifications already marked as read")]
+    #[serde(default)]
+    pub all: bool,
+    #[schemars(description = "Only notifications where you are directly participating or mentioned")]
+    #[serde(default)]
+    pub participating: bool,
+    #[schemars(description = "Only notifications updated after this ISO 8601 timestamp")]
+    pub since: Option<String>,
+    #[schemars(description = "Only notifications of this repository, as owner/repo")]
+    pub repo: Option<String>,
+    #[schemars(description = "Only notifications with this reason, e.g. review_requested, mention, assign or ci_activity")]
+    pub reason: Option<String>,
+    #[schemars(description = "Maximum number of notifications to return, most recently updated first")]
+    pub limit: Option<u32>,
+}
+
+/// Mark notifications read request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct MarkNotificationReadParam {
+    #[schemars(description = "Notification thread ID, from list_notifications; omit for the bulk form with repo")]
+    pub thread_id: Option<String>,
+    #[schemars(description = "Bulk form: mark every notification of this repository (owner/repo) as read")]
+    pub repo: Option<String>,
+    #[schemars(description = "Bulk form: only notifications updated before this ISO 8601 timestamp, defaults to now")]
+    pub before: Option<String>,
+}
+
+/// Notification thread request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct NotificationThreadParam {
+    #[schemars(description = "Notification thread ID, from list_notifications")]
+    pub thread_id: String,
+}
+
+/// Notification subscription to a thread
+#[derive(Debug, Clone, Copy, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ThreadSubscriptionMode {
+    /// Notify about all further activity
+    Subscribe,
+    /// Mute all further activity, even when mentioned
+    Ignore,
+    /// Back to the default: notify only when participating or mentioned
+    Unsubscribe,
+}
+
+/// Set thread subscription request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct SetThreadSubscriptionParam {
+    #[schemars(description = "Notification thread ID, from list_notifications; omit when url is set")]
+    pub thread_id: Option<String>,
+    #[schemars(description = "Web URL of the issue or pull request whose notification thread to change, e.g. https://github.com/octo/app/issues/12")]
+    pub url: Option<String>,
+    pub mode: ThreadSubscriptionMode,
+}
+
+/// List repositories request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ListReposParam {
+    #[schemars(description = "JSON fields to return instead of name, description and url")]
+    pub fields: Option<Vec<String>>,
+}
+
+/// Repository view request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct RepoViewParam {
+    pub owner: String,
+    pub repo: String,
+    #[schemars(description = "JSON fields to return instead of the defaults, e.g. primaryLanguage or repositoryTopics")]
+    pub fields: Option<Vec<String>>,
+}
+
+/// List issues request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ListIssuesParam {
+    pub owner: String,
+    pub repo: String,
+    #[schemars(description = "JSON fields to return instead of number, title, state and url, e.g. labels or assignees")]
+    pub fields: Option<Vec<String>>,
+}
+
+/// List pull requests request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ListPRsParam {
+    pub owner: String,
+    pub repo: String,
+    #[schemars(description = "JSON fields to return instead of number, title, state and url, e.g. isDraft or headRefName")]
+    pub fields: Option<Vec<String>>,
+}
+
+/// List discussions request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ListDiscussionsParam {
+    pub owner: String,
+    pub repo: String,
+    #[schemars(description = "Maximum number of discussions to return, newest first, defaults to 30 (max 100)")]
+    pub limit: Option<u32>,
+    #[schemars(description = "Only discussions in this category, by name or slug, e.g. Q&A or ideas")]
+    pub category: Option<String>,
+}
+
+/// Get discussion request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GetDiscussionParam {
+    pub owner: String,
+    pub repo: String,
+    pub number: u64,
+}
+
+/// Create discussion comment request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct CreateDiscussionCommentParam {
+    pub owner: String,
+    pub repo: String,
+    pub discussion_number: u64,
+    #[schemars(description = "Comment body in Markdown")]
+    pub body: String,
+    #[schemars(description = "Node ID of a top-level comment to reply to in its thread, from get_discussion")]
+    pub reply_to_id: Option<String>,
+}
+
+/// Create discussion request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct CreateDiscussionParam {
+    pub owner: String,
+    pub repo: String,
+    pub title: String,
+    #[schemars(description = "Discussion body in Markdown")]
+    pub body: String,
+    #[schemars(description = "Discussion category node ID, or the category name or slug, e.g. General or q-a")]
+    pub category_id: String,
+}
+
 /// Create issue request parameters
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct CreateIssueParam {
@@ -33,6 +528,20 @@ pub struct CreateIssueParam {
     pub repo: Option<String>,
 }
 
+/// Create issue from template request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct CreateIssueFromTemplateParam {
+    pub owner: String,
+    pub repo: String,
+    #[schemars(description = "Markdown template file name under .github/ISSUE_TEMPLATE, e.g. bug_report.md")]
+    pub template: String,
+    #[schemars(description = "Values for the template's {{placeholder}}s; every placeholder must be given")]
+    #[serde(default)]
+    pub fields: HashMap<String, String>,
+    #[schemars(description = "Issue title, defaults to the template's title")]
+    pub title: Option<String>,
+}
+
 /// Create PR request parameters
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct CreatePRParam {
@@ -40,6 +549,8 @@ pub struct CreatePRParam {
     pub body: Option<String>,
     pub base: String,
     pub head: String,
+    #[schemars(description = "Owner of the fork containing the head branch (or owner/repo). Required for PRs opened from a fork")]
+    pub head_repo: Option<String>,
     pub repo: Option<String>,
 }
 
@@ -50,277 +561,7985 @@ pub struct CloneRepoParam {
     pub directory: Option<String>,
 }
 
-/// GitHub MCP Service
-#[derive(Clone)]
-pub struct GitHubService {
-    last_result: Arc<Mutex<Option<CommandResult>>>,
+/// Search repositories request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct SearchReposParam {
+    pub query: String,
+    #[schemars(description = "Restrict search to repositories of a user or organization")]
+    pub owner: Option<String>,
+    pub language: Option<String>,
+    pub topic: Option<String>,
+    #[schemars(description = "Stars qualifier such as >100 or 10..50")]
+    pub stars: Option<String>,
+    pub limit: Option<u32>,
 }
 
-/// Run GitHub CLI command and return result
-fn run_gh_command(args: Vec<String>) -> CommandResult {
-    let output = Command::new("gh")
-        .args(&args)
-        .output();
-    
-    match output {
-        Ok(output) => {
-            let success = output.status.success();
-            let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-            let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-            
-            CommandResult {
-                success,
-                output: stdout,
-                error: if !success { Some(stderr) } else { None },
+/// Issue or pull request state filter
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum IssueState {
+    Open,
+    Closed,
+}
+
+/// Search issues or pull requests request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct SearchIssuesParam {
+    pub query: String,
+    #[schemars(description = "Restrict search to a repository (owner/repo)")]
+    pub repo: Option<String>,
+    #[schemars(description = "Restrict search to repositories of a user or organization")]
+    pub owner: Option<String>,
+    pub author: Option<String>,
+    pub state: Option<IssueState>,
+    pub label: Option<String>,
+    pub limit: Option<u32>,
+}
+
+/// Where code search terms must match
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CodeMatchIn {
+    File,
+    Path,
+}
+
+/// Search code request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct SearchCodeParam {
+    pub query: String,
+    #[schemars(description = "Restrict search to a repository (owner/repo)")]
+    pub repo: Option<String>,
+    #[schemars(description = "Restrict search to repositories of a user or organization")]
+    pub owner: Option<String>,
+    pub language: Option<String>,
+    pub filename: Option<String>,
+    #[schemars(description = "File extension without leading dot")]
+    pub extension: Option<String>,
+    #[schemars(description = "Directory path the file must be under")]
+    pub path: Option<String>,
+    pub match_in: Option<CodeMatchIn>,
+    pub limit: Option<u32>,
+}
+
+/// Sort key for commit search
+#[derive(Debug, Clone, Copy, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum CommitSort {
+    AuthorDate,
+    CommitterDate,
+}
+
+/// Search commits request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct SearchCommitsParam {
+    pub query: String,
+    pub author: Option<String>,
+    pub committer: Option<String>,
+    #[schemars(description = "Restrict search to a repository (owner/repo)")]
+    pub repo: Option<String>,
+    #[schemars(description = "Restrict search to repositories of a user or organization")]
+    pub owner: Option<String>,
+    #[schemars(description = "Earliest author date (YYYY-MM-DD)")]
+    pub author_date_from: Option<String>,
+    #[schemars(description = "Latest author date (YYYY-MM-DD)")]
+    pub author_date_to: Option<String>,
+    #[schemars(description = "Only merge commits (true) or only non-merge commits (false)")]
+    pub merge: Option<bool>,
+    pub hash: Option<String>,
+    pub limit: Option<u32>,
+    pub sort: Option<CommitSort>,
+}
+
+/// Account type for user search
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum AccountType {
+    User,
+    Org,
+}
+
+/// Search users request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct SearchUsersParam {
+    pub query: String,
+    #[serde(rename = "type")]
+    pub account_type: Option<AccountType>,
+    pub location: Option<String>,
+    #[schemars(description = "Language of the account's repositories")]
+    pub language: Option<String>,
+    #[schemars(description = "Followers qualifier such as >100 or 10..50")]
+    pub followers: Option<String>,
+    pub limit: Option<u32>,
+}
+
+/// Kind of entity searched by the unified search tool
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchKind {
+    Repos,
+    Code,
+    Issues,
+    Prs,
+    Commits,
+    Users,
+}
+
+impl SearchKind {
+    /// Filter fields that apply to this kind of search
+    fn allowed_filters(self) -> &'static [&'static str] {
+        match self {
+            SearchKind::Repos => &["owner", "language", "topic", "stars"],
+            SearchKind::Code => &["repo", "owner", "language", "filename", "extension", "path", "match_in"],
+            SearchKind::Issues | SearchKind::Prs => &["repo", "owner", "author", "state", "label"],
+            SearchKind::Commits => &[
+                "repo",
+                "owner",
+                "author",
+                "committer",
+                "author_date_from",
+                "author_date_to",
+                "merge",
+                "hash",
+            ],
+            SearchKind::Users => &["type", "location", "language", "followers"],
+        }
+    }
+}
+
+/// Filters shared by all search kinds; each kind accepts only a subset
+#[derive(Debug, Default, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct SearchFilters {
+    #[schemars(description = "owner/repo (code, issues, prs, commits)")]
+    pub repo: Option<String>,
+    #[schemars(description = "User or organization (repos, code, issues, prs, commits)")]
+    pub owner: Option<String>,
+    #[schemars(description = "Language (repos, code, users)")]
+    pub language: Option<String>,
+    #[schemars(description = "Topic (repos)")]
+    pub topic: Option<String>,
+    #[schemars(description = "Stars qualifier such as >100 (repos)")]
+    pub stars: Option<String>,
+    #[schemars(description = "File name (code)")]
+    pub filename: Option<String>,
+    #[schemars(description = "File extension without leading dot (code)")]
+    pub extension: Option<String>,
+    #[schemars(description = "Directory path (code)")]
+    pub path: Option<String>,
+    #[schemars(description = "Match in file contents or path (code)")]
+    pub match_in: Option<CodeMatchIn>,
+    #[schemars(description = "Author login (issues, prs, commits)")]
+    pub author: Option<String>,
+    #[schemars(description = "State (issues, prs)")]
+    pub state: Option<IssueState>,
+    #[schemars(description = "Label name (issues, prs)")]
+    pub label: Option<String>,
+    #[schemars(description = "Committer login (commits)")]
+    pub committer: Option<String>,
+    #[schemars(description = "Earliest author date YYYY-MM-DD (commits)")]
+    pub author_date_from: Option<String>,
+    #[schemars(description = "Latest author date YYYY-MM-DD (commits)")]
+    pub author_date_to: Option<String>,
+    #[schemars(description = "Only merge or only non-merge commits (commits)")]
+    pub merge: Option<bool>,
+    #[schemars(description = "Commit hash (commits)")]
+    pub hash: Option<String>,
+    #[serde(rename = "type")]
+    #[schemars(description = "Account type (users)")]
+    pub account_type: Option<AccountType>,
+    #[schemars(description = "Location (users)")]
+    pub location: Option<String>,
+    #[schemars(description = "Followers qualifier such as >100 (users)")]
+    pub followers: Option<String>,
+}
+
+/// Unified search request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct SearchParam {
+    pub kind: SearchKind,
+    pub query: String,
+    #[serde(default)]
+    pub filters: SearchFilters,
+    pub limit: Option<u32>,
+}
+
+/// User lookup request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct UserParam {
+    pub login: String,
+}
+
+/// Authentication login request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct AuthLoginParam {
+    #[schemars(description = "GitHub host to authenticate with, defaults to github.com")]
+    pub github_host: Option<String>,
+    #[schemars(description = "Additional OAuth scopes to request")]
+    pub scopes: Option<Vec<String>>,
+    #[schemars(description = "Personal access token, passed to gh on stdin")]
+    pub with_token: Option<String>,
+}
+
+/// Authentication logout request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct AuthLogoutParam {
+    #[schemars(description = "GitHub host to log out of, defaults to github.com")]
+    pub github_host: Option<String>,
+    #[schemars(description = "Account to log out of when several are logged in on the host")]
+    pub username: Option<String>,
+}
+
+/// List labels request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ListLabelsParam {
+    pub owner: String,
+    pub repo: String,
+    pub limit: Option<u32>,
+}
+
+/// Create label request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct CreateLabelParam {
+    pub owner: String,
+    pub repo: String,
+    pub name: String,
+    #[schemars(description = "Six hex digit color, with or without leading '#'")]
+    pub color: String,
+    pub description: Option<String>,
+    #[schemars(description = "Update color and description when a label with this name already exists")]
+    #[serde(default)]
+    pub update_if_exists: bool,
+}
+
+/// Edit label request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct EditLabelParam {
+    pub owner: String,
+    pub repo: String,
+    pub name: String,
+    pub new_name: Option<String>,
+    #[schemars(description = "Six hex digit color, with or without leading '#'")]
+    pub color: Option<String>,
+    pub description: Option<String>,
+}
+
+/// Delete label request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct DeleteLabelParam {
+    pub owner: String,
+    pub repo: String,
+    pub name: String,
+    #[schemars(description = "Must be true to delete the label")]
+    #[serde(default)]
+    pub confirm: bool,
+}
+
+/// Token login request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct LoginWithTokenParam {
+    #[schemars(description = "Personal access token, passed to gh on stdin")]
+    pub token: String,
+}
+
+/// Clone labels request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct CloneLabelsParam {
+    pub source_owner: String,
+    pub source_repo: String,
+    pub target_owner: String,
+    pub target_repo: String,
+    #[schemars(description = "Update target labels whose color or description differ from the source")]
+    #[serde(default)]
+    pub overwrite: bool,
+    #[schemars(description = "Only report what would change without modifying the target")]
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Repository label as returned by `gh label list`
+#[derive(Debug, Clone, Deserialize)]
+struct Label {
+    name: String,
+    color: String,
+    #[serde(default)]
+    description: String,
+}
+
+/// State filter for list tools
+#[derive(Debug, Clone, Copy, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ListState {
+    Open,
+    Closed,
+    All,
+}
+
+impl ListState {
+    fn as_str(self) -> &'static str {
+        match self {
+            ListState::Open => "open",
+            ListState::Closed => "closed",
+            ListState::All => "all",
+        }
+    }
+}
+
+impl IssueState {
+    fn as_str(self) -> &'static str {
+        match self {
+            IssueState::Open => "open",
+            IssueState::Closed => "closed",
+        }
+    }
+}
+
+/// Pagination parameters shared by API-backed list tools
+#[derive(Debug, Default, Deserialize, schemars::JsonSchema)]
+pub struct PageParam {
+    #[schemars(description = "Items per page, 1-100, defaults to 30")]
+    pub per_page: Option<u32>,
+    #[schemars(description = "Page number starting at 1; use next_page from the previous response")]
+    pub page: Option<u32>,
+}
+
+/// List milestones request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ListMilestonesParam {
+    pub owner: String,
+    pub repo: String,
+    #[schemars(description = "Defaults to open")]
+    pub state: Option<ListState>,
+    #[serde(flatten)]
+    pub page: PageParam,
+}
+
+/// List commits request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ListCommitsParam {
+    pub owner: String,
+    pub repo: String,
+    #[schemars(description = "Branch, tag or SHA to start listing from, defaults to the default branch")]
+    pub sha: Option<String>,
+    #[schemars(description = "Only commits touching this file path")]
+    pub path: Option<String>,
+    #[schemars(description = "Author login or email")]
+    pub author: Option<String>,
+    #[schemars(description = "Only commits after this ISO 8601 timestamp")]
+    pub since: Option<String>,
+    #[schemars(description = "Only commits before this ISO 8601 timestamp")]
+    pub until: Option<String>,
+    #[serde(flatten)]
+    pub page: PageParam,
+}
+
+/// List branches request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ListBranchesParam {
+    pub owner: String,
+    pub repo: String,
+    #[schemars(description = "Only protected (true) or unprotected (false) branches")]
+    pub protected: Option<bool>,
+    #[serde(flatten)]
+    pub page: PageParam,
+}
+
+/// List contributors request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ListContributorsParam {
+    pub owner: String,
+    pub repo: String,
+    #[serde(flatten)]
+    pub page: PageParam,
+}
+
+/// Resolve ref request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ResolveRefParam {
+    pub owner: String,
+    pub repo: String,
+    #[schemars(description = "Branch name, tag name or SHA")]
+    pub ref_name: String,
+}
+
+/// Pull request files request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct PRFilesParam {
+    pub owner: String,
+    pub repo: String,
+    pub number: u64,
+    #[serde(flatten)]
+    pub page: PageParam,
+}
+
+/// Pull request diff request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GetPRDiffParam {
+    pub owner: String,
+    pub repo: String,
+    pub number: u64,
+}
+
+/// Pull request reviews request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ListPRReviewsParam {
+    pub owner: String,
+    pub repo: String,
+    pub number: u64,
+}
+
+/// Dismiss pull request review request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct DismissPRReviewParam {
+    pub owner: String,
+    pub repo: String,
+    pub number: u64,
+    #[schemars(description = "ID of the review, from list_pr_reviews")]
+    pub review_id: u64,
+    #[schemars(description = "Reason for dismissing the review, required by GitHub")]
+    pub message: String,
+}
+
+/// Pull request review comments request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ListPRReviewCommentsParam {
+    pub owner: String,
+    pub repo: String,
+    pub number: u64,
+    #[serde(flatten)]
+    pub page: PageParam,
+}
+
+/// Reply to pull request review comment request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ReplyPRReviewCommentParam {
+    pub owner: String,
+    pub repo: String,
+    pub number: u64,
+    #[schemars(description = "ID of the inline comment to reply to, from list_pr_review_comments")]
+    pub comment_id: u64,
+    pub body: String,
+}
+
+/// Edit issue comment request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct EditCommentParam {
+    pub owner: String,
+    pub repo: String,
+    #[schemars(description = "ID of a comment on an issue or pull request conversation")]
+    pub comment_id: u64,
+    #[schemars(description = "New comment body, replacing the old one")]
+    pub body: String,
+}
+
+/// Delete issue comment request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct DeleteCommentParam {
+    pub owner: String,
+    pub repo: String,
+    #[schemars(description = "ID of a comment on an issue or pull request conversation")]
+    pub comment_id: u64,
+    #[schemars(description = "Must be true to delete the comment")]
+    #[serde(default)]
+    pub confirm: bool,
+}
+
+/// Create milestone request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct CreateMilestoneParam {
+    pub owner: String,
+    pub repo: String,
+    pub title: String,
+    pub description: Option<String>,
+    #[schemars(description = "Due date as RFC3339 timestamp or YYYY-MM-DD (midnight UTC)")]
+    pub due_on: Option<String>,
+    pub state: Option<IssueState>,
+}
+
+/// Edit milestone request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct EditMilestoneParam {
+    pub owner: String,
+    pub repo: String,
+    pub number: u64,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    #[schemars(description = "Due date as RFC3339 timestamp or YYYY-MM-DD (midnight UTC)")]
+    pub due_on: Option<String>,
+    pub state: Option<IssueState>,
+}
+
+/// Milestone reference request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct MilestoneParam {
+    pub owner: String,
+    pub repo: String,
+    pub number: u64,
+}
+
+/// Switch account request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct SwitchAccountParam {
+    #[schemars(description = "GitHub host of the account, defaults to github.com")]
+    pub hostname: Option<String>,
+    #[schemars(description = "Login of the account to make active")]
+    pub user: String,
+}
+
+/// Application whose secret store is targeted. Each app has a separate store:
+/// actions secrets are exposed to workflow runs, dependabot secrets only to
+/// Dependabot-triggered runs and private registry access, and codespaces
+/// secrets only inside codespaces
+#[derive(Debug, Clone, Copy, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SecretApp {
+    Actions,
+    Codespaces,
+    Dependabot,
+}
+
+impl SecretApp {
+    fn as_str(self) -> &'static str {
+        match self {
+            SecretApp::Actions => "actions",
+            SecretApp::Codespaces => "codespaces",
+            SecretApp::Dependabot => "dependabot",
+        }
+    }
+}
+
+/// List repository secrets request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ListRepoSecretsParam {
+    pub owner: String,
+    pub repo: String,
+    #[schemars(description = "Secret store to list, defaults to actions. Use dependabot for Dependabot registry credentials, which are a separate store")]
+    pub app: Option<SecretApp>,
+}
+
+/// Set repository secret request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct SetRepoSecretParam {
+    pub owner: String,
+    pub repo: String,
+    #[schemars(description = "Uppercase letters, digits and underscores, not starting with GITHUB_")]
+    pub name: String,
+    pub value: String,
+    #[schemars(description = "Secret store to write, defaults to actions. Use dependabot for Dependabot registry credentials, which are a separate store")]
+    pub app: Option<SecretApp>,
+}
+
+/// Delete repository secret request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct DeleteRepoSecretParam {
+    pub owner: String,
+    pub repo: String,
+    pub name: String,
+    #[schemars(description = "Secret store to delete from, defaults to actions. Use dependabot for Dependabot registry credentials, which are a separate store")]
+    pub app: Option<SecretApp>,
+    #[schemars(description = "Must be true to delete the secret")]
+    #[serde(default)]
+    pub confirm: bool,
+}
+
+/// Set environment secret request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct SetEnvSecretParam {
+    pub owner: String,
+    pub repo: String,
+    pub env: String,
+    #[schemars(description = "Uppercase letters, digits and underscores, not starting with GITHUB_")]
+    pub name: String,
+    pub value: String,
+}
+
+/// Delete environment secret request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct DeleteEnvSecretParam {
+    pub owner: String,
+    pub repo: String,
+    pub env: String,
+    pub name: String,
+    #[schemars(description = "Must be true to delete the secret")]
+    #[serde(default)]
+    pub confirm: bool,
+}
+
+/// Which organization repositories can use a secret
+#[derive(Debug, Clone, Copy, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SecretVisibility {
+    All,
+    Private,
+    Selected,
+}
+
+impl SecretVisibility {
+    fn as_str(self) -> &'static str {
+        match self {
+            SecretVisibility::All => "all",
+            SecretVisibility::Private => "private",
+            SecretVisibility::Selected => "selected",
+        }
+    }
+}
+
+/// Role of a member in an organization
+#[derive(Debug, Clone, Copy, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum OrgRole {
+    All,
+    Admin,
+    Member,
+}
+
+/// List organization members request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ListOrgMembersParam {
+    pub org: String,
+    #[schemars(description = "Only members with this role, defaults to all")]
+    pub role: Option<OrgRole>,
+    #[schemars(description = "Maximum number of members to return, all when omitted")]
+    pub limit: Option<u32>,
+}
+
+/// Role to give a member of an organization
+#[derive(Debug, Clone, Copy, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum MembershipRole {
+    Member,
+    Admin,
+}
+
+impl MembershipRole {
+    fn as_str(self) -> &'static str {
+        match self {
+            MembershipRole::Member => "member",
+            MembershipRole::Admin => "admin",
+        }
+    }
+}
+
+/// Set organization membership request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct SetOrgMembershipParam {
+    pub org: String,
+    #[schemars(description = "Login of the user to add or update")]
+    pub username: String,
+    #[schemars(description = "Role in the organization: member or admin (owner)")]
+    pub role: MembershipRole,
+}
+
+/// List teams request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ListTeamsParam {
+    pub org: String,
+    #[schemars(description = "Maximum number of teams to return, all when omitted")]
+    pub limit: Option<u32>,
+}
+
+/// List team repositories request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ListTeamReposParam {
+    pub org: String,
+    #[schemars(description = "Team slug, from list_teams")]
+    pub team_slug: String,
+    #[schemars(description = "Maximum number of repositories to return, all when omitted")]
+    pub limit: Option<u32>,
+}
+
+/// Permission of a team on a repository
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum TeamRepoPermission {
+    Pull,
+    Triage,
+    Push,
+    Maintain,
+    Admin,
+    /// Remove the repository from the team
+    None,
+}
+
+impl TeamRepoPermission {
+    fn as_str(self) -> &'static str {
+        match self {
+            TeamRepoPermission::Pull => "pull",
+            TeamRepoPermission::Triage => "triage",
+            TeamRepoPermission::Push => "push",
+            TeamRepoPermission::Maintain => "maintain",
+            TeamRepoPermission::Admin => "admin",
+            TeamRepoPermission::None => "none",
+        }
+    }
+}
+
+/// Set team repository permission request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct SetTeamRepoPermissionParam {
+    pub org: String,
+    #[schemars(description = "Team slug, from list_teams")]
+    pub team_slug: String,
+    pub owner: String,
+    pub repo: String,
+    #[schemars(description = "Permission to grant: pull, triage, push, maintain or admin; none removes the repository from the team")]
+    pub permission: TeamRepoPermission,
+}
+
+/// Organization audit log request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct OrgAuditLogParam {
+    pub org: String,
+    #[schemars(description = "Raw audit log search phrase, e.g. repo:octo/app; combined with the other filters")]
+    pub phrase: Option<String>,
+    #[schemars(description = "Only events of this action or action category, e.g. protected_branch.update or repo")]
+    pub action: Option<String>,
+    #[schemars(description = "Only events performed by this login")]
+    pub actor: Option<String>,
+    #[schemars(description = "Only events on or after this date, YYYY-MM-DD")]
+    pub since: Option<String>,
+    #[schemars(description = "Only events on or before this date, YYYY-MM-DD")]
+    pub until: Option<String>,
+    #[schemars(description = "Maximum number of events to return, newest first, defaults to 100 (max 1000)")]
+    pub limit: Option<u32>,
+}
+
+/// Role of a member in a team
+#[derive(Debug, Clone, Copy, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum TeamRole {
+    All,
+    Maintainer,
+    Member,
+}
+
+/// Team members request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct TeamMembersParam {
+    pub org: String,
+    #[schemars(description = "Team slug, from list_teams")]
+    pub team_slug: String,
+    #[schemars(description = "Only members with this team role, defaults to all")]
+    pub role: Option<TeamRole>,
+    #[schemars(description = "Maximum number of members to return, all when omitted")]
+    pub limit: Option<u32>,
+}
+
+/// List organization secrets request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ListOrgSecretsParam {
+    pub org: String,
+    #[schemars(description = "Secret store to list, defaults to actions. Use dependabot for Dependabot registry credentials, which are a separate store")]
+    pub app: Option<SecretApp>,
+}
+
+/// Set organization secret request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct SetOrgSecretParam {
+    pub org: String,
+    #[schemars(description = "Uppercase letters, digits and underscores, not starting with GITHUB_")]
+    pub name: String,
+    pub value: String,
+    pub visibility: SecretVisibility,
+    #[schemars(description = "Repository names in the organization; required when visibility is selected")]
+    pub repos: Option<Vec<String>>,
+    #[schemars(description = "Secret store to write, defaults to actions. Use dependabot for Dependabot registry credentials, which are a separate store")]
+    pub app: Option<SecretApp>,
+}
+
+/// List variables request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ListVariablesParam {
+    pub owner: String,
+    pub repo: String,
+    #[schemars(description = "Deployment environment; repository level when omitted")]
+    pub env: Option<String>,
+}
+
+/// Set variable request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct SetVariableParam {
+    pub owner: String,
+    pub repo: String,
+    #[schemars(description = "Deployment environment; repository level when omitted")]
+    pub env: Option<String>,
+    pub name: String,
+    pub value: String,
+}
+
+/// Delete variable request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct DeleteVariableParam {
+    pub owner: String,
+    pub repo: String,
+    #[schemars(description = "Deployment environment; repository level when omitted")]
+    pub env: Option<String>,
+    pub name: String,
+}
+
+/// List projects request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ListProjectsParam {
+    #[schemars(description = "User or organization login, defaults to the current user")]
+    pub owner: Option<String>,
+    #[schemars(description = "Whether owner is an organization. Optional: owner resolves users and organizations alike, so it only requires owner to be set")]
+    pub is_org: Option<bool>,
+    #[schemars(description = "Maximum number of projects, defaults to 30")]
+    pub limit: Option<u32>,
+}
+
+/// Project items request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ProjectItemsParam {
+    #[schemars(description = "User or organization login, defaults to the current user")]
+    pub owner: Option<String>,
+    #[schemars(description = "Project number, from list_projects")]
+    pub number: u64,
+    #[schemars(description = "Maximum number of items, defaults to 30")]
+    pub limit: Option<u32>,
+    #[schemars(description = "Only items whose Status field equals this value, case-insensitive")]
+    pub status: Option<String>,
+}
+
+/// Project fields request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ProjectFieldsParam {
+    #[schemars(description = "User or organization login, defaults to the current user")]
+    pub owner: Option<String>,
+    #[schemars(description = "Project number, from list_projects")]
+    pub number: u64,
+}
+
+/// Archive project item request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ProjectArchiveItemParam {
+    #[schemars(description = "User or organization login, defaults to the current user")]
+    pub owner: Option<String>,
+    #[schemars(description = "Project number, from list_projects")]
+    pub number: u64,
+    #[schemars(description = "Item ID, from project_items; omit when status is set")]
+    pub item_id: Option<String>,
+    #[schemars(description = "Bulk mode: every item whose Status field equals this value, case-insensitive")]
+    pub status: Option<String>,
+}
+
+/// Remove project item request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ProjectRemoveItemParam {
+    #[schemars(description = "User or organization login, defaults to the current user")]
+    pub owner: Option<String>,
+    #[schemars(description = "Project number, from list_projects")]
+    pub number: u64,
+    #[schemars(description = "Item ID, from project_items; omit when status is set")]
+    pub item_id: Option<String>,
+    #[schemars(description = "Bulk mode: every item whose Status field equals this value, case-insensitive")]
+    pub status: Option<String>,
+    #[schemars(description = "Must be true to remove the items")]
+    #[serde(default)]
+    pub confirm: bool,
+}
+
+/// Create project request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct CreateProjectParam {
+    #[schemars(description = "User or organization login, defaults to the current user")]
+    pub owner: Option<String>,
+    #[schemars(description = "Whether owner is an organization. Optional: owner resolves users and organizations alike, so it only requires owner to be set")]
+    pub is_org: Option<bool>,
+    pub title: String,
+}
+
+/// Add draft issue request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ProjectAddDraftParam {
+    #[schemars(description = "User or organization login, defaults to the current user")]
+    pub owner: Option<String>,
+    #[schemars(description = "Project number, from list_projects or create_project")]
+    pub number: u64,
+    pub title: String,
+    pub body: Option<String>,
+}
+
+/// Add project item request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ProjectAddItemParam {
+    #[schemars(description = "User or organization login, defaults to the current user")]
+    pub owner: Option<String>,
+    #[schemars(description = "Project number, from list_projects")]
+    pub number: u64,
+    #[schemars(description = "URL of the issue or pull request to add")]
+    pub url: String,
+}
+
+/// Set project field request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ProjectSetFieldParam {
+    #[schemars(description = "User or organization login, defaults to the current user")]
+    pub owner: Option<String>,
+    #[schemars(description = "Project number, from list_projects")]
+    pub number: u64,
+    #[schemars(description = "Item ID, from project_items or project_add_item")]
+    pub item_id: String,
+    #[schemars(description = "Field name, e.g. Status")]
+    pub field: String,
+    #[schemars(description = "New value: an option name for single-select fields, an iteration title for iteration fields, otherwise text, a number or a YYYY-MM-DD date")]
+    pub value: String,
+}
+
+/// List codespaces request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ListCodespacesParam {
+    #[schemars(description = "Only codespaces of this repository, as owner/repo")]
+    pub repo: Option<String>,
+}
+
+/// Codespace name request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct CodespaceParam {
+    pub name: String,
+}
+
+/// Create codespace request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct CreateCodespaceParam {
+    pub owner: String,
+    pub repo: String,
+    #[schemars(description = "Defaults to the repository's default branch")]
+    pub branch: Option<String>,
+    #[schemars(description = "Machine type name, e.g. basicLinux32gb; an invalid name returns the available types")]
+    pub machine: Option<String>,
+    #[schemars(description = "Path of devcontainer.json in the repository")]
+    pub devcontainer_path: Option<String>,
+    #[schemars(description = "Minutes of inactivity before the codespace stops")]
+    pub idle_timeout_minutes: Option<u32>,
+    #[schemars(description = "Days a stopped codespace is kept before it is deleted automatically")]
+    pub retention_days: Option<u32>,
+}
+
+/// Codespace exec request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct CodespaceExecParam {
+    pub name: String,
+    #[schemars(description = "Shell command run in the codespace, passed as a single argument")]
+    pub command: String,
+    #[schemars(description = "Seconds before the command is killed, including SSH connection setup which can take 30+ seconds on a cold codespace; defaults to 120, at most 1800")]
+    pub timeout_secs: Option<u64>,
+}
+
+/// Who can reach a forwarded codespace port
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PortVisibility {
+    Private,
+    Org,
+    Public,
+}
+
+impl PortVisibility {
+    fn as_str(self) -> &'static str {
+        match self {
+            PortVisibility::Private => "private",
+            PortVisibility::Org => "org",
+            PortVisibility::Public => "public",
+        }
+    }
+}
+
+/// Codespace ports request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct CodespacePortsParam {
+    pub name: String,
+    #[schemars(description = "Port whose visibility to change; the ports are only listed when omitted")]
+    pub port: Option<u16>,
+    #[schemars(description = "New visibility for port")]
+    pub visibility: Option<PortVisibility>,
+    #[schemars(description = "Must be true to make a port public")]
+    #[serde(default)]
+    pub confirm: bool,
+}
+
+/// Codespace logs request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct CodespaceLogsParam {
+    pub name: String,
+    #[schemars(description = "Kilobytes from the end of the log to return, defaults to 64")]
+    pub tail_kb: Option<u32>,
+}
+
+/// Delete codespace request parameters
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct DeleteCodespaceParam {
+    #[schemars(description = "Codespace to delete; omit when all is set")]
+    pub name: Option<String>,
+    #[schemars(description = "Bulk mode: delete every codespace idle for at least days")]
+    #[serde(default)]
+    pub all: bool,
+    #[schemars(description = "Minimum days since last use for bulk deletion, required when all is set")]
+    pub days: Option<u32>,
+    #[schemars(description = "Limit bulk deletion to codespaces of this repository, as owner/repo")]
+    pub repo: Option<String>,
+    #[schemars(description = "Must be true to delete codespaces")]
+    #[serde(default)]
+    pub confirm: bool,
+}
+
+/// GitHub MCP Service
+#[derive(Clone)]
+pub struct GitHubService {
+    last_result: Arc<Mutex<Option<CommandResult>>>,
+    /// Results of read-only commands keyed by their serialized arguments
+    cache: Option<Arc<Cache<String, CommandResult>>>,
+    /// Log commands instead of executing them
+    dry_run: bool,
+    /// Record of every executed command, when configured
+    audit: Option<Arc<AuditLogger>>,
+    /// Attempts made for read-only commands failing with a transient error
+    retry_attempts: u32,
+    /// Delay before the first retry
+    retry_base_delay: Duration,
+    /// Whether codespace_exec may run commands
+    allow_codespace_exec: bool,
+    /// GitHub CLI executable every command runs
+    gh_binary: Arc<str>,
+    /// Whether the installed gh can merge API pages itself with --slurp
+    gh_supports_slurp: bool,
+    /// Profile of the authenticated user, fetched once per account
+    current_user: Arc<Mutex<Option<Value>>>,
+    /// Tool calls hold a read guard while running; shutdown takes the write
+    /// guard to wait for them and keep new calls from starting
+    in_flight: Arc<RwLock<()>>,
+    /// Permits for running gh processes, shared by all tool calls
+    gh_permits: Arc<Semaphore>,
+    /// Invocation counts and latencies per tool
+    metrics: Arc<Metrics>,
+    /// Latest API quota per rate limit resource, seen on `gh api -i` responses or fetched by rate_limit
+    rate_limits: Arc<Mutex<HashMap<String, Value>>>,
+}
+
+/// Host used when a tool does not specify one
+const DEFAULT_GITHUB_HOST: &str = "github.com";
+
+/// Minimum supported GitHub CLI version as (major, minor)
+const MIN_GH_VERSION: (u32, u32) = (2, 0);
+
+/// First gh version whose `api --paginate` supports `--slurp`
+const SLURP_GH_VERSION: (u32, u32) = (2, 48);
+
+/// Major and minor number of a gh version such as "2.40.1"
+fn gh_major_minor(version: &str) -> (u32, u32) {
+    let mut parts = version.split('.').map(|part| part.parse::<u32>().unwrap_or(0));
+    (parts.next().unwrap_or(0), parts.next().unwrap_or(0))
+}
+
+/// Check that gh is installed and recent enough, returning its version
+pub fn check_gh_installed(binary: &str) -> Result<String> {
+    let output = match std::process::Command::new(binary).arg("--version").output() {
+        Ok(output) => output,
+        Err(e) => {
+            tracing::error!("GitHub CLI ({}) not found, install it from https://cli.github.com or set GH_MCP_BINARY: {}", binary, e);
+            return Err(anyhow::anyhow!("GitHub CLI ({}) is not installed or not in PATH: {}", binary, e));
+        }
+    };
+    
+    // Output looks like "gh version 2.40.1 (2023-12-13)"
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let version = stdout
+        .split_whitespace()
+        .skip_while(|word| *word != "version")
+        .nth(1)
+        .ok_or_else(|| anyhow::anyhow!("Unexpected gh --version output: {}", stdout.trim()))?
+        .to_string();
+    
+    if gh_major_minor(&version) < MIN_GH_VERSION {
+        tracing::error!(
+            "GitHub CLI {} is too old, version {}.{} or newer is required",
+            version, MIN_GH_VERSION.0, MIN_GH_VERSION.1
+        );
+        return Err(anyhow::anyhow!(
+            "GitHub CLI {} is not supported, version {}.{} or newer is required",
+            version, MIN_GH_VERSION.0, MIN_GH_VERSION.1
+        ));
+    }
+    
+    Ok(version)
+}
+
+/// How long a GitHub CLI command may run before it is killed
+#[derive(Debug, Clone, Copy)]
+enum CommandTimeout {
+    /// Regular commands and API calls
+    Default,
+    /// Slow operations such as commit search or cloning
+    Long,
+    /// Caller-chosen limit
+    Custom(Duration),
+}
+
+impl CommandTimeout {
+    fn duration(self) -> Duration {
+        match self {
+            CommandTimeout::Default => Duration::from_secs(60),
+            CommandTimeout::Long => Duration::from_secs(300),
+            CommandTimeout::Custom(duration) => duration,
+        }
+    }
+}
+
+/// Build a successful tool result, adding non-empty stderr output as a second item
+fn command_success(text: String, result: &CommandResult) -> CallToolResult {
+    let mut contents = vec![Content::text(text)];
+    if let Some(stderr) = result.stderr.as_deref().filter(|s| !s.trim().is_empty()) {
+        contents.push(Content::text(stderr.to_string()));
+    }
+    CallToolResult::success(contents)
+}
+
+/// Category of a failed command, exposed to clients as `error_kind`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    Auth,
+    NotFound,
+    RateLimited,
+    Validation,
+    Unknown,
+}
+
+/// gh exits with this code when authentication is required
+const GH_EXIT_AUTH: i32 = 4;
+
+/// Classify a failed command from its exit code and stderr
+fn classify_error(result: &CommandResult) -> ErrorKind {
+    let stderr = result.error.as_deref().unwrap_or_default().to_lowercase();
+    let mentions = |patterns: &[&str]| patterns.iter().any(|p| stderr.contains(p));
+
+    // Rate limiting is checked first because GitHub reports it with HTTP 403
+    if mentions(&["rate limit", "http 429"]) {
+        ErrorKind::RateLimited
+    } else if result.exit_code == GH_EXIT_AUTH
+        || mentions(&["http 401", "bad credentials", "gh auth login", "authentication", "not logged in"])
+    {
+        ErrorKind::Auth
+    } else if mentions(&["http 404", "not found", "could not resolve to"]) {
+        ErrorKind::NotFound
+    } else if mentions(&["http 422", "validation failed", "unknown flag", "unknown field", "invalid", "required flag"]) {
+        ErrorKind::Validation
+    } else {
+        ErrorKind::Unknown
+    }
+}
+
+/// Error data describing a failed command: stderr, exit code and error kind
+fn command_error_data(result: &CommandResult) -> Value {
+    json!({
+        "error": result.error.clone().unwrap_or_default(),
+        "exit_code": result.exit_code,
+        "error_kind": classify_error(result),
+    })
+}
+
+/// Whether a failed command hit a transient network or server error worth retrying.
+/// Auth, not found and validation failures are permanent and never retried
+fn is_retryable(result: &CommandResult) -> bool {
+    if result.success {
+        return false;
+    }
+    if matches!(classify_error(result), ErrorKind::Auth | ErrorKind::NotFound | ErrorKind::Validation) {
+        return false;
+    }
+    let stderr = result.error.as_deref().unwrap_or_default().to_lowercase();
+    [
+        "http 429",
+        "secondary rate limit",
+        "http 500",
+        "http 502",
+        "http 503",
+        "http 504",
+        "i/o timeout",
+        "tls handshake timeout",
+        "connection reset",
+        "connection refused",
+        "no such host",
+        "unexpected eof",
+    ]
+    .iter()
+    .any(|pattern| stderr.contains(pattern))
+}
+
+/// Build an MCP error from a failed command
+fn command_error(message: &'static str, result: &CommandResult) -> McpError {
+    McpError::internal_error(message, Some(command_error_data(result)))
+}
+
+/// Whether a failed API call was rejected with HTTP 403
+fn is_forbidden(result: &CommandResult) -> bool {
+    result.error.as_deref().is_some_and(|e| e.contains("HTTP 403"))
+}
+
+/// Keys of a `gh project item-list` item that are not project field values
+const PROJECT_ITEM_KEYS: [&str; 3] = ["id", "title", "content"];
+
+/// Flatten a `gh project item-list` item into one row with its field values
+fn shape_project_item(item: &Value) -> Value {
+    let content = &item["content"];
+    let fields: serde_json::Map<String, Value> = item
+        .as_object()
+        .map(|item| {
+            item.iter()
+                .filter(|(key, _)| !PROJECT_ITEM_KEYS.contains(&key.as_str()))
+                .map(|(key, value)| {
+                    // Iteration and milestone values are objects, keep their title
+                    let value = match value.get("title") {
+                        Some(title) => title.clone(),
+                        None => value.clone(),
+                    };
+                    (key.clone(), value)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    json!({
+        "id": item["id"],
+        "type": content["type"],
+        "title": item["title"],
+        "number": content["number"],
+        "repository": content["repository"],
+        "url": content["url"],
+        "fields": fields,
+    })
+}
+
+/// Resolve the `--owner` of a project command, which gh accepts for users and organizations alike.
+/// is_org is only checked against a missing owner, since the current user is never an organization
+fn project_owner(owner: Option<String>, is_org: Option<bool>) -> Result<String, McpError> {
+    match owner {
+        Some(owner) => Ok(owner),
+        None if is_org == Some(true) => Err(McpError::invalid_params("is_org needs owner set to the organization login", None)),
+        None => Ok("@me".to_string()),
+    }
+}
+
+/// Build an MCP error for a failed project command, pointing at the missing scope when that is the cause
+fn project_command_error(message: &'static str, result: &CommandResult) -> McpError {
+    let stderr = result.error.as_deref().unwrap_or_default();
+    if stderr.contains("missing required scopes") || stderr.contains("read:project") {
+        return McpError::invalid_request(
+            "The token lacks the project scope; run `gh auth refresh -s project` and try again",
+            Some(command_error_data(result)),
+        );
+    }
+    command_error(message, result)
+}
+
+/// Project items scanned when items are selected by ID or status
+const PROJECT_ITEM_SCAN_LIMIT: u32 = 1000;
+
+/// GraphQL query for the iterations of a project iteration field
+const PROJECT_ITERATIONS_QUERY: &str = "query($id: ID!) { node(id: $id) { ... on ProjectV2IterationField { configuration { iterations { id title startDate duration } completedIterations { id title startDate duration } } } } }";
+
+/// The iteration whose date range, startDate plus duration days, contains `today`
+fn current_iteration(iterations: &[Value], today: NaiveDate) -> Option<&Value> {
+    iterations.iter().find(|iteration| {
+        let start = iteration["startDate"]
+            .as_str()
+            .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok());
+        let days = iteration["duration"].as_i64();
+        match (start, days) {
+            (Some(start), Some(days)) => start <= today && today < start + chrono::Duration::days(days),
+            _ => false,
+        }
+    })
+}
+
+/// Find the entry whose `name_key` matches `name` case-insensitively, or list the valid names
+fn find_by_name<'a>(entries: &'a [Value], name_key: &str, name: &str, what: &str) -> Result<&'a Value, McpError> {
+    entries
+        .iter()
+        .find(|entry| entry[name_key].as_str().is_some_and(|n| n.eq_ignore_ascii_case(name)))
+        .ok_or_else(|| {
+            let valid: Vec<&Value> = entries.iter().map(|entry| &entry[name_key]).collect();
+            McpError::invalid_params(
+                format!("Unknown {} {:?}", what, name),
+                Some(json!({"valid": valid})),
+            )
+        })
+}
+
+/// `gh project item-edit` flag for a value of a plain (non-select) field, guessed from the value
+fn project_value_flag(value: &str) -> &'static str {
+    if value.parse::<f64>().is_ok() {
+        "--number"
+    } else if NaiveDate::parse_from_str(value, "%Y-%m-%d").is_ok() {
+        "--date"
+    } else {
+        "--text"
+    }
+}
+
+/// Fields requested from `gh codespace list`
+const CODESPACE_FIELDS: &str = "name,repository,state,gitStatus,machineName,lastUsedAt";
+
+/// Codespace states in which the machine is still being set up and cannot be deleted
+const CODESPACE_PROVISIONING_STATES: [&str; 5] = ["Queued", "Provisioning", "Awaiting", "Starting", "Rebuilding"];
+
+/// How long create_codespace waits for a new codespace to become available
+const CODESPACE_READY_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Delay between codespace state checks while waiting for it to become available
+const CODESPACE_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Default codespace_exec timeout, long enough for SSH setup on a cold codespace
+const CODESPACE_EXEC_DEFAULT_TIMEOUT_SECS: u64 = 120;
+
+/// Upper bound for the codespace_exec timeout
+const CODESPACE_EXEC_MAX_TIMEOUT_SECS: u64 = 1800;
+
+/// Characters of stdout and stderr kept from a codespace_exec command
+const CODESPACE_EXEC_MAX_OUTPUT: usize = 64 * 1024;
+
+/// Default amount of codespace log returned, in kilobytes
+const CODESPACE_LOG_DEFAULT_TAIL_KB: u32 = 64;
+
+/// Keep at most the last `max` bytes of text, starting on a character boundary
+fn tail_bytes(text: &str, max: usize) -> &str {
+    let mut start = text.len().saturating_sub(max);
+    while !text.is_char_boundary(start) {
+        start += 1;
+    }
+    &text[start..]
+}
+
+/// Whole days since a codespace was last used, None when lastUsedAt is missing or malformed
+fn codespace_idle_days(codespace: &Value, now: DateTime<Utc>) -> Option<i64> {
+    let last_used = DateTime::parse_from_rfc3339(codespace["lastUsedAt"].as_str()?).ok()?;
+    Some((now - last_used.with_timezone(&Utc)).num_days())
+}
+
+/// Validate a label color and strip the leading '#'
+fn normalize_label_color(color: &str) -> Result<String, McpError> {
+    let color = color.strip_prefix('#').unwrap_or(color);
+    if color.len() == 6 && color.chars().all(|c| c.is_ascii_hexdigit()) {
+        Ok(color.to_lowercase())
+    } else {
+        Err(McpError::invalid_params(
+            "color must be 6 hex digits, e.g. d73a4a",
+            Some(json!({"color": color})),
+        ))
+    }
+}
+
+/// Require an explicit confirmation flag before a destructive operation
+fn require_confirm(confirm: bool, action: &str) -> Result<(), McpError> {
+    if confirm {
+        Ok(())
+    } else {
+        Err(McpError::invalid_params(
+            format!("Set confirm to true to {}", action),
+            None,
+        ))
+    }
+}
+
+/// Build `gh label edit` arguments
+fn label_edit_args(
+    repo: String,
+    name: String,
+    new_name: Option<String>,
+    color: Option<String>,
+    description: Option<String>,
+) -> Vec<String> {
+    let mut args = vec!["label".to_string(), "edit".to_string(), name, "--repo".to_string(), repo];
+    if let Some(new_name) = new_name {
+        args.push("--name".to_string());
+        args.push(new_name);
+    }
+    if let Some(color) = color {
+        args.push("--color".to_string());
+        args.push(color);
+    }
+    if let Some(description) = description {
+        args.push("--description".to_string());
+        args.push(description);
+    }
+    args
+}
+
+/// Find the active account in `gh auth status` output
+fn active_user_from_status(status: &str) -> Option<String> {
+    let mut users = Vec::new();
+    for line in status.lines() {
+        let line = line.trim();
+        // Newer gh reports "account <user>", older versions "as <user>"
+        let user = line
+            .split_once(" account ")
+            .or_else(|| line.split_once(" as "))
+            .filter(|_| line.contains("Logged in to"))
+            .and_then(|(_, rest)| rest.split_whitespace().next());
+        if let Some(user) = user {
+            users.push(user.to_string());
+        }
+        if line.contains("Active account: true") {
+            return users.pop();
+        }
+    }
+    users.into_iter().next()
+}
+
+/// Find the host of the active account in `gh auth status` output
+fn active_host_from_status(status: &str) -> Option<String> {
+    let mut hosts = Vec::new();
+    for line in status.lines() {
+        let line = line.trim();
+        let host = line
+            .split_once("Logged in to ")
+            .and_then(|(_, rest)| rest.split_whitespace().next());
+        if let Some(host) = host {
+            hosts.push(host.to_string());
+        }
+        if line.contains("Active account: true") {
+            return hosts.pop();
+        }
+    }
+    hosts.into_iter().next()
+}
+
+/// Environment variables gh reads an authentication token from
+const TOKEN_ENV_VARS: [&str; 4] = ["GH_TOKEN", "GITHUB_TOKEN", "GH_ENTERPRISE_TOKEN", "GITHUB_ENTERPRISE_TOKEN"];
+
+/// Find an executable the way the shell would: paths are used as given, bare names are looked up on PATH
+fn find_in_path(name: &str) -> Option<std::path::PathBuf> {
+    if name.contains(std::path::is_separator) {
+        return std::fs::canonicalize(name).ok();
+    }
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path).find_map(|dir| {
+        let candidate = dir.join(name);
+        if candidate.is_file() {
+            return Some(candidate);
+        }
+        let candidate = candidate.with_extension("exe");
+        candidate.is_file().then_some(candidate)
+    })
+}
+
+/// Directory gh reads its configuration from, following gh's own lookup order
+fn gh_config_dir() -> Option<std::path::PathBuf> {
+    let var = |name: &str| std::env::var_os(name).filter(|value| !value.is_empty());
+    if let Some(dir) = var("GH_CONFIG_DIR") {
+        return Some(dir.into());
+    }
+    if let Some(dir) = var("XDG_CONFIG_HOME") {
+        return Some(std::path::PathBuf::from(dir).join("gh"));
+    }
+    if cfg!(windows) {
+        if let Some(dir) = var("AppData") {
+            return Some(std::path::PathBuf::from(dir).join("GitHub CLI"));
+        }
+    }
+    var("HOME").map(|home| std::path::PathBuf::from(home).join(".config").join("gh"))
+}
+
+/// Reject empty tokens before they reach gh
+fn require_token(token: &str) -> Result<&str, McpError> {
+    let token = token.trim();
+    if token.is_empty() {
+        return Err(McpError::invalid_params("token must not be empty", None));
+    }
+    Ok(token)
+}
+
+/// Copy of a command result with its output replaced, for results that carry secrets
+fn redact_output(result: &CommandResult) -> CommandResult {
+    CommandResult {
+        output: REDACTED.to_string(),
+        ..result.clone()
+    }
+}
+
+/// Decide per source label whether cloning creates, updates or skips it
+fn plan_label_clone(source: &[Label], target: &[Label], overwrite: bool) -> Vec<Value> {
+    source
+        .iter()
+        .map(|label| {
+            // Label names are case-insensitive on GitHub
+            let existing = target.iter().find(|t| t.name.eq_ignore_ascii_case(&label.name));
+            let action = match existing {
+                None => "create",
+                Some(t) if t.color.eq_ignore_ascii_case(&label.color) && t.description == label.description => "skip",
+                Some(_) if overwrite => "update",
+                Some(_) => "skip",
+            };
+            json!({
+                "name": label.name,
+                "color": label.color,
+                "description": label.description,
+                "action": action,
+            })
+        })
+        .collect()
+}
+
+/// Validate a due date, converting date-only input to midnight UTC
+fn normalize_due_on(due_on: &str) -> Result<String, McpError> {
+    if let Ok(date) = DateTime::parse_from_rfc3339(due_on) {
+        return Ok(date.with_timezone(&Utc).to_rfc3339_opts(SecondsFormat::Secs, true));
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(due_on, "%Y-%m-%d") {
+        return Ok(format!("{}T00:00:00Z", date.format("%Y-%m-%d")));
+    }
+    Err(McpError::invalid_params(
+        "due_on must be an RFC3339 timestamp or a YYYY-MM-DD date",
+        Some(json!({"due_on": due_on})),
+    ))
+}
+
+/// Build `gh api` field arguments for milestone create and edit
+fn milestone_fields(
+    title: Option<String>,
+    description: Option<String>,
+    due_on: Option<String>,
+    state: Option<IssueState>,
+) -> Result<Vec<String>, McpError> {
+    let mut args = Vec::new();
+    let due_on = due_on.as_deref().map(normalize_due_on).transpose()?;
+    let fields = [
+        ("title", title),
+        ("description", description),
+        ("due_on", due_on),
+        ("state", state.map(|state| state.as_str().to_string())),
+    ];
+    for (name, value) in fields {
+        if let Some(value) = value {
+            args.push("-f".to_string());
+            args.push(format!("{}={}", name, value));
+        }
+    }
+    Ok(args)
+}
+
+/// Reduce a milestone API object to its useful fields plus completion percentage
+fn shape_milestone(milestone: &Value) -> Value {
+    let open = milestone["open_issues"].as_u64().unwrap_or(0);
+    let closed = milestone["closed_issues"].as_u64().unwrap_or(0);
+    let completion = if open + closed == 0 {
+        0.0
+    } else {
+        (closed as f64 * 1000.0 / (open + closed) as f64).round() / 10.0
+    };
+    json!({
+        "number": milestone["number"],
+        "title": milestone["title"],
+        "description": milestone["description"],
+        "state": milestone["state"],
+        "due_on": milestone["due_on"],
+        "open_issues": open,
+        "closed_issues": closed,
+        "completion_percent": completion,
+        "url": milestone["html_url"],
+    })
+}
+
+/// Check a secret name against GitHub's naming rules
+fn validate_secret_name(name: &str) -> Result<(), McpError> {
+    let problem = if name.is_empty() {
+        Some("must not be empty")
+    } else if name.starts_with("GITHUB_") {
+        Some("must not start with the reserved GITHUB_ prefix")
+    } else if name.starts_with(|c: char| c.is_ascii_digit()) {
+        Some("must not start with a digit")
+    } else if !name.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_') {
+        Some("may only contain uppercase letters, digits and underscores")
+    } else {
+        None
+    };
+    match problem {
+        Some(problem) => Err(McpError::invalid_params(
+            format!("Secret name {} {}", name, problem),
+            Some(json!({"name": name})),
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Arguments selecting the secret store of an application
+fn secret_app_args(app: Option<SecretApp>) -> Vec<String> {
+    match app {
+        Some(app) => vec!["--app".to_string(), app.as_str().to_string()],
+        None => Vec::new(),
+    }
+}
+
+/// Arguments for deleting a repository secret. The app is always passed
+/// explicitly so a delete can never fall through to gh's default store
+fn delete_secret_args(name: &str, repo: &str, app: Option<SecretApp>) -> Vec<String> {
+    let mut args = vec![
+        "secret".to_string(),
+        "delete".to_string(),
+        name.to_string(),
+        "--repo".to_string(),
+        repo.to_string(),
+    ];
+    args.extend(secret_app_args(Some(app.unwrap_or(SecretApp::Actions))));
+    args
+}
+
+/// Copy of a command result with every occurrence of a secret value removed
+fn redact_secret(result: &CommandResult, secret: &str) -> CommandResult {
+    if secret.is_empty() {
+        return result.clone();
+    }
+    let scrub = |text: &str| text.replace(secret, REDACTED);
+    CommandResult {
+        output: scrub(&result.output),
+        error: result.error.as_deref().map(scrub),
+        stderr: result.stderr.as_deref().map(scrub),
+        ..result.clone()
+    }
+}
+
+/// Reduce a deployment environment API object to its gates and branch policy
+fn shape_environment(env: &Value) -> Value {
+    let rules = env["protection_rules"].as_array().cloned().unwrap_or_default();
+    let reviewers: Vec<Value> = rules
+        .iter()
+        .filter(|rule| rule["type"] == "required_reviewers")
+        .flat_map(|rule| rule["reviewers"].as_array().cloned().unwrap_or_default())
+        .map(|reviewer| {
+            // Users have a login, teams a slug
+            let name = if reviewer["reviewer"]["login"].is_string() {
+                reviewer["reviewer"]["login"].clone()
+            } else {
+                reviewer["reviewer"]["slug"].clone()
+            };
+            json!({"type": reviewer["type"], "name": name})
+        })
+        .collect();
+    let wait_timer = rules
+        .iter()
+        .find(|rule| rule["type"] == "wait_timer")
+        .map(|rule| rule["wait_timer"].clone())
+        .unwrap_or(Value::Null);
+    json!({
+        "name": env["name"],
+        "required_reviewers": reviewers,
+        "wait_timer_minutes": wait_timer,
+        "deployment_branch_policy": env["deployment_branch_policy"],
+        "url": env["html_url"],
+    })
+}
+
+/// Arguments selecting a repository and optional environment
+fn scope_args(owner: &str, repo: &str, env: Option<&str>) -> Vec<String> {
+    let mut args = vec!["--repo".to_string(), format!("{}/{}", owner, repo)];
+    if let Some(env) = env {
+        args.push("--env".to_string());
+        args.push(env.to_string());
+    }
+    args
+}
+
+/// Extract the next page number from a `Link` response header
+fn next_page_from_link(link: &str) -> Option<u32> {
+    link.split(',')
+        .find(|part| part.contains("rel=\"next\""))
+        .and_then(|part| part.split(['?', '&', '>']).find_map(|param| param.strip_prefix("page=")))
+        .and_then(|page| page.parse().ok())
+}
+
+/// Reduce a commit API object to its useful fields
+fn shape_commit(commit: &Value) -> Value {
+    let headline = commit["commit"]["message"]
+        .as_str()
+        .and_then(|message| message.lines().next())
+        .unwrap_or_default();
+    let author = if commit["author"]["login"].is_string() {
+        &commit["author"]["login"]
+    } else {
+        &commit["commit"]["author"]["name"]
+    };
+    json!({
+        "sha": commit["sha"],
+        "headline": headline,
+        "author": author,
+        "date": commit["commit"]["author"]["date"],
+        "url": commit["html_url"],
+    })
+}
+
+/// Reduce a branch API object to its useful fields
+fn shape_branch(branch: &Value) -> Value {
+    json!({
+        "name": branch["name"],
+        "sha": branch["commit"]["sha"],
+        "protected": branch["protected"],
+    })
+}
+
+/// Reduce a repository API object in a list to its useful fields
+fn shape_listed_repo(repo: &Value) -> Value {
+    json!({
+        "full_name": repo["full_name"],
+        "description": repo["description"],
+        "language": repo["language"],
+        "stars": repo["stargazers_count"],
+        "url": repo["html_url"],
+    })
+}
+
+/// Repository permissions from most to least privileged
+const REPO_PERMISSIONS: [&str; 5] = ["admin", "maintain", "push", "triage", "pull"];
+
+/// A team's access to a repository: its role name, or else the highest permission granted
+fn team_repo_permission(repo: &Value) -> Value {
+    if repo["role_name"].is_string() {
+        return repo["role_name"].clone();
+    }
+    REPO_PERMISSIONS
+        .iter()
+        .find(|permission| repo["permissions"][**permission] == true)
+        .map(|permission| json!(permission))
+        .unwrap_or(Value::Null)
+}
+
+/// Reduce a contributor API object to its useful fields
+fn shape_contributor(contributor: &Value) -> Value {
+    json!({
+        "login": contributor["login"],
+        "contributions": contributor["contributions"],
+    })
+}
+
+/// Reduce a pull request file API object to its useful fields
+fn shape_pr_file(file: &Value) -> Value {
+    json!({
+        "filename": file["filename"],
+        "status": file["status"],
+        "additions": file["additions"],
+        "deletions": file["deletions"],
+        "changes": file["changes"],
+        "previous_filename": file["previous_filename"],
+    })
+}
+
+/// Reduce a pull request review API object to its useful fields
+fn shape_review(review: &Value) -> Value {
+    json!({
+        "id": review["id"],
+        "user": review["user"]["login"],
+        "state": review["state"],
+        "body": review["body"],
+        "submittedAt": review["submitted_at"],
+        "url": review["html_url"],
+    })
+}
+
+/// Reduce an inline review comment API object to its useful fields
+fn shape_review_comment(comment: &Value) -> Value {
+    // Comments on outdated diffs lose their line but keep original_line
+    let line = if comment["line"].is_null() {
+        &comment["original_line"]
+    } else {
+        &comment["line"]
+    };
+    json!({
+        "id": comment["id"],
+        "path": comment["path"],
+        "line": line,
+        "body": comment["body"],
+        "author": comment["user"]["login"],
+        "createdAt": comment["created_at"],
+        "url": comment["html_url"],
+    })
+}
+
+/// Open pull requests inspected by list_mergeable_prs
+const MERGEABLE_PR_SCAN_LIMIT: u32 = 100;
+
+/// Commands run at the same time when a tool fans out over many issues or pull requests
+const FAN_OUT_CONCURRENCY: usize = 8;
+
+/// Whether every check and commit status in a `statusCheckRollup` passed
+fn checks_passing(rollup: &Value) -> bool {
+    rollup.as_array().is_none_or(|checks| {
+        checks.iter().all(|check| {
+            if check["__typename"] == "StatusContext" {
+                check["state"] == "SUCCESS"
+            } else {
+                check["status"] == "COMPLETED"
+                    && matches!(check["conclusion"].as_str(), Some("SUCCESS" | "NEUTRAL" | "SKIPPED"))
+            }
+        })
+    })
+}
+
+/// Whether a pull request from `gh pr view` is ready to merge: not a draft,
+/// no conflicts, approved or not requiring review, and all checks passing
+fn pr_ready_to_merge(pr: &Value) -> bool {
+    pr["isDraft"] != true
+        && pr["mergeable"] == "MERGEABLE"
+        && matches!(pr["reviewDecision"].as_str(), None | Some("") | Some("APPROVED"))
+        && checks_passing(&pr["statusCheckRollup"])
+}
+
+/// Reduce an issue timeline event to its kind, actor, time and event-specific details
+fn shape_timeline_event(event: &Value) -> Value {
+    let kind = event["event"].as_str().unwrap_or_default();
+    // Comments and reviews carry a user, commits an author, everything else an actor
+    let actor = [&event["actor"]["login"], &event["user"]["login"], &event["author"]["name"]]
+        .into_iter()
+        .find(|actor| actor.is_string())
+        .unwrap_or(&Value::Null);
+    let created_at = [&event["created_at"], &event["submitted_at"], &event["author"]["date"]]
+        .into_iter()
+        .find(|time| time.is_string())
+        .unwrap_or(&Value::Null);
+    let details = match kind {
+        "labeled" | "unlabeled" => json!({"label": event["label"]["name"]}),
+        "assigned" | "unassigned" => json!({"assignee": event["assignee"]["login"]}),
+        "milestoned" | "demilestoned" => json!({"milestone": event["milestone"]["title"]}),
+        "renamed" => json!({"from": event["rename"]["from"], "to": event["rename"]["to"]}),
+        "review_requested" | "review_request_removed" => json!({
+            "reviewer": event["requested_reviewer"]["login"],
+            "team": event["requested_team"]["name"],
+        }),
+        "commented" => json!({"body": event["body"], "url": event["html_url"]}),
+        "reviewed" => json!({"state": event["state"], "body": event["body"], "url": event["html_url"]}),
+        "committed" => json!({"sha": event["sha"], "message": event["message"]}),
+        "cross-referenced" => json!({
+            "source": event["source"]["issue"]["html_url"],
+            "title": event["source"]["issue"]["title"],
+        }),
+        "referenced" | "closed" | "merged" => json!({"commit_id": event["commit_id"]}),
+        _ => Value::Null,
+    };
+    json!({
+        "event": kind,
+        "actor": actor,
+        "createdAt": created_at,
+        "details": details,
+    })
+}
+
+/// Split a template into its `---` delimited front matter, as simple `key: value` pairs, and its body
+fn split_front_matter(text: &str) -> (HashMap<String, String>, &str) {
+    let mut front_matter = HashMap::new();
+    let Some(rest) = text.strip_prefix("---\n").or_else(|| text.strip_prefix("---\r\n")) else {
+        return (front_matter, text);
+    };
+    let Some(end) = rest.find("\n---") else {
+        return (front_matter, text);
+    };
+    let mut last_key: Option<String> = None;
+    for line in rest[..end].lines() {
+        // A block list item (`  - bug`) continues the list of the key above it
+        if let Some(item) = line.trim_start().strip_prefix("- ") {
+            if let Some(value) = last_key.as_ref().and_then(|key| front_matter.get_mut(key)) {
+                if !value.is_empty() {
+                    value.push_str(", ");
+                }
+                value.push_str(item.trim());
+            }
+        } else if let Some((key, value)) = line.split_once(':') {
+            let value = value.trim().trim_matches(|c| c == '\'' || c == '"');
+            front_matter.insert(key.trim().to_string(), value.to_string());
+            last_key = Some(key.trim().to_string());
+        }
+    }
+    // The body starts on the line after the closing ---
+    let closing = &rest[end + 1..];
+    let body = closing.find('\n').map_or("", |newline| &closing[newline + 1..]);
+    (front_matter, body)
+}
+
+/// Parse an inline front matter list such as `bug, triage` or `["bug", "triage"]`
+fn front_matter_list(value: &str) -> Vec<String> {
+    value
+        .trim_matches(|c| c == '[' || c == ']')
+        .split(',')
+        .map(|item| item.trim().trim_matches(|c| c == '\'' || c == '"').to_string())
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+/// Replace every `{{name}}` placeholder with its field value, or return the names without a value
+fn render_template(template: &str, fields: &HashMap<String, String>) -> Result<String, Vec<String>> {
+    let mut rendered = String::with_capacity(template.len());
+    let mut missing = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        rendered.push_str(&rest[..start]);
+        let name = rest[start + 2..start + 2 + len].trim();
+        match fields.get(name) {
+            Some(value) => rendered.push_str(value),
+            None if !missing.iter().any(|m| m == name) => missing.push(name.to_string()),
+            None => {}
+        }
+        rest = &rest[start + 2 + len + 2..];
+    }
+    rendered.push_str(rest);
+    if missing.is_empty() {
+        Ok(rendered)
+    } else {
+        Err(missing)
+    }
+}
+
+/// Resolve the `--head` value, namespacing the branch with the fork owner when given
+fn resolve_pr_head(head: &str, head_repo: Option<&str>) -> Result<String, McpError> {
+    let Some(head_repo) = head_repo else {
+        return Ok(head.to_string());
+    };
+    if head.contains(':') {
+        return Err(McpError::invalid_params(
+            "head must be a plain branch name when head_repo is set",
+            Some(json!({"head": head, "head_repo": head_repo})),
+        ));
+    }
+    // gh expects `owner:branch`, so only the owner part of `owner/repo` is used
+    let owner = head_repo.split('/').next().unwrap_or_default();
+    if owner.is_empty() {
+        return Err(McpError::invalid_params(
+            "head_repo must name the fork owner",
+            Some(json!({"head_repo": head_repo})),
+        ));
+    }
+    Ok(format!("{}:{}", owner, head))
+}
+
+/// Response headers gh_api reports: rate limit, pagination and request tracing
+const API_RESPONSE_HEADERS: [&str; 9] = [
+    "x-ratelimit-limit",
+    "x-ratelimit-remaining",
+    "x-ratelimit-reset",
+    "x-ratelimit-used",
+    "x-ratelimit-resource",
+    "link",
+    "location",
+    "retry-after",
+    "x-github-request-id",
+];
+
+/// Characters of a gh_api response body returned before it is truncated
+const GH_API_MAX_BODY: usize = 100 * 1024;
+
+/// Percent-encode a query string or URL path component
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// Build `gh api -i` arguments for a raw API request
+fn gh_api_args(param: &GhApiParam) -> Result<Vec<String>, McpError> {
+    let path = param.path.trim();
+    if path.starts_with("http://") || path.starts_with("https://") {
+        return Err(McpError::invalid_params(
+            format!("Pass an API path such as repos/octo/app instead of the URL {}", path),
+            None,
+        ));
+    }
+    if param.raw_body.is_some() && !param.fields.is_empty() {
+        return Err(McpError::invalid_params("Set either fields or raw_body, not both", None));
+    }
+    let mut url = path.to_string();
+    if !param.query.is_empty() {
+        let query: Vec<String> = param
+            .query
+            .iter()
+            .map(|(name, value)| format!("{}={}", percent_encode(name), percent_encode(value)))
+            .collect();
+        url.push(if url.contains('?') { '&' } else { '?' });
+        url.push_str(&query.join("&"));
+    }
+    let method = param.method.unwrap_or(ApiMethod::Get);
+    if param.paginate && method != ApiMethod::Get {
+        return Err(McpError::invalid_params("paginate only works with GET", None));
+    }
+    let mut args = vec![
+        "api".to_string(),
+        "--method".to_string(),
+        method.as_str().to_string(),
+        url,
+    ];
+    for (name, value) in &param.fields {
+        // -F keeps booleans, numbers and null typed; -f sends strings verbatim, even ones starting with @
+        let (flag, value) = match value {
+            Value::String(value) => ("-f", value.clone()),
+            Value::Bool(_) | Value::Number(_) | Value::Null => ("-F", value.to_string()),
+            Value::Array(_) | Value::Object(_) => {
+                return Err(McpError::invalid_params(
+                    format!("Field {} is nested; send nested JSON through raw_body", name),
+                    None,
+                ))
+            }
+        };
+        args.push(flag.to_string());
+        args.push(format!("{}={}", name, value));
+    }
+    args.extend(gh_api_header_args(param)?);
+    if param.raw_body.is_some() {
+        args.push("--input".to_string());
+        args.push("-".to_string());
+    }
+    Ok(args)
+}
+
+/// Error for a paginated gh_api request whose pages are not lists
+fn not_a_list_error() -> McpError {
+    McpError::invalid_params(
+        "paginate needs a list endpoint whose pages are arrays or wrap a single array; call it without paginate",
+        None,
+    )
+}
+
+/// Build `-H` arguments for the headers of a raw API request
+fn gh_api_header_args(param: &GhApiParam) -> Result<Vec<String>, McpError> {
+    let mut args = Vec::new();
+    let accept = param.accept.iter().map(|accept| ("Accept", accept));
+    for (name, value) in param.headers.iter().map(|(n, v)| (n.as_str(), v)).chain(accept) {
+        if name.contains([':', '\r', '\n']) || value.contains(['\r', '\n']) {
+            return Err(McpError::invalid_params(format!("Invalid header {}", name), None));
+        }
+        args.push("-H".to_string());
+        args.push(format!("{}: {}", name, value));
+    }
+    Ok(args)
+}
+
+/// Items of one page of a list endpoint: the page itself when it is an array, or the single
+/// array it wraps, as in search results ({total_count, items}) or workflow runs
+fn page_items(page: Value) -> Option<Vec<Value>> {
+    match page {
+        Value::Array(items) => Some(items),
+        Value::Object(fields) => {
+            let mut arrays = fields.into_iter().filter_map(|(_, value)| match value {
+                Value::Array(items) => Some(items),
+                _ => None,
+            });
+            match (arrays.next(), arrays.next()) {
+                (Some(items), None) => Some(items),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// URL of the next page from a Link response header
+fn next_page_url(link: &str) -> Option<&str> {
+    link.split(',').find_map(|part| {
+        let (url, rel) = part.split_once(';')?;
+        rel.contains("rel=\"next\"")
+            .then(|| url.trim().trim_start_matches('<').trim_end_matches('>'))
+    })
+}
+
+/// Maximum length of a single code search match fragment
+const MAX_FRAGMENT_LEN: usize = 300;
+
+/// Response of a `gh api -i` call split into status, headers and body
+struct ApiResponse {
+    status: u16,
+    headers: HashMap<String, String>,
+    body: String,
+}
+
+/// Split `gh api -i` output into status line, headers and body
+fn parse_api_response(output: &str) -> ApiResponse {
+    let normalized = output.replace("\r\n", "\n");
+    let (head, body) = normalized
+        .split_once("\n\n")
+        .unwrap_or((normalized.as_str(), ""));
+
+    let mut lines = head.lines();
+    let status = lines
+        .next()
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|code| code.parse().ok())
+        .unwrap_or(0);
+    let headers = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_ascii_lowercase(), value.trim().to_string()))
+        .collect();
+
+    ApiResponse {
+        status,
+        headers,
+        body: body.to_string(),
+    }
+}
+
+/// Extract rate limit information from API response headers
+fn rate_limit_from_headers(headers: &HashMap<String, String>) -> Value {
+    let number = |name: &str| headers.get(name).and_then(|v| v.parse::<u64>().ok());
+    json!({
+        "limit": number("x-ratelimit-limit"),
+        "remaining": number("x-ratelimit-remaining"),
+        "reset": number("x-ratelimit-reset"),
+    })
+}
+
+/// Rate limit resources reported by the rate_limit tool
+const RATE_LIMIT_RESOURCES: [&str; 4] = ["core", "search", "graphql", "code_search"];
+
+/// Quota of one rate limit resource, with its reset as epoch seconds and ISO time
+fn rate_limit_entry(limit: Option<u64>, remaining: Option<u64>, used: Option<u64>, reset: Option<u64>) -> Value {
+    let reset_at = reset
+        .and_then(|reset| DateTime::from_timestamp(reset as i64, 0))
+        .map(|time| time.to_rfc3339_opts(SecondsFormat::Secs, true));
+    json!({
+        "limit": limit,
+        "remaining": remaining,
+        "used": used,
+        "reset": reset,
+        "reset_at": reset_at,
+        "observed_at": Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+    })
+}
+
+/// Shorten a text fragment to at most `max` characters
+fn truncate_fragment(fragment: &str, max: usize) -> String {
+    match fragment.char_indices().nth(max) {
+        Some((idx, _)) => format!("{}…", &fragment[..idx]),
+        None => fragment.to_string(),
+    }
+}
+
+/// Parse output of `gh api --paginate --jq '.[]'`, which emits one JSON value per line across all pages
+fn parse_json_lines(output: &str) -> Result<Vec<Value>, McpError> {
+    output
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(parse_json_output)
+        .collect()
+}
+
+/// Build an MCP error for a failed organization command, explaining when membership is required
+fn org_command_error(message: &'static str, org: &str, result: &CommandResult) -> McpError {
+    // Members and teams of an organization are hidden from non-members as 403 or 404
+    if is_forbidden(result) || classify_error(result) == ErrorKind::NotFound {
+        return McpError::invalid_request(
+            format!("{}: you must be a member of {} (or the organization does not exist)", message, org),
+            Some(command_error_data(result)),
+        );
+    }
+    command_error(message, result)
+}
+
+/// GraphQL query for the node ID and discussion categories of a repository
+const DISCUSSION_CATEGORIES_QUERY: &str = "query($owner: String!, $repo: String!) { repository(owner: $owner, name: $repo) { id discussionCategories(first: 100) { nodes { id name slug } } } }";
+
+/// GraphQL query for one discussion with its first comments
+const DISCUSSION_QUERY: &str = "query($owner: String!, $repo: String!, $number: Int!) { repository(owner: $owner, name: $repo) { discussion(number: $number) { number title body url createdAt upvoteCount category { name } author { login } answer { id } comments(first: 50) { totalCount nodes { id body createdAt upvoteCount author { login } } } } } }";
+
+/// GraphQL query for the node ID of a discussion
+const DISCUSSION_ID_QUERY: &str = "query($owner: String!, $repo: String!, $number: Int!) { repository(owner: $owner, name: $repo) { discussion(number: $number) { id } } }";
+
+/// GraphQL mutation adding a comment, or a threaded reply, to a discussion
+const ADD_DISCUSSION_COMMENT_MUTATION: &str = "mutation($discussionId: ID!, $body: String!, $replyToId: ID) { addDiscussionComment(input: {discussionId: $discussionId, body: $body, replyToId: $replyToId}) { comment { id url } } }";
+
+/// GraphQL mutation creating a discussion
+const CREATE_DISCUSSION_MUTATION: &str = "mutation($repositoryId: ID!, $categoryId: ID!, $title: String!, $body: String!) { createDiscussion(input: {repositoryId: $repositoryId, categoryId: $categoryId, title: $title, body: $body}) { discussion { number url } } }";
+
+/// Build `gh api graphql` arguments listing the newest discussions of a repository, optionally in one category
+fn discussions_query_args(owner: &str, repo: &str, first: u32, category_id: Option<&str>) -> Vec<String> {
+    // categoryId is only declared when filtering, as GitHub rejects an explicit null category
+    let (declaration, argument) = match category_id {
+        Some(_) => (", $categoryId: ID!", ", categoryId: $categoryId"),
+        None => ("", ""),
+    };
+    let query = format!(
+        "query($owner: String!, $repo: String!, $first: Int!{}) {{ repository(owner: $owner, name: $repo) {{ discussions(first: $first{}, orderBy: {{field: CREATED_AT, direction: DESC}}) {{ nodes {{ number title createdAt url category {{ name }} author {{ login }} }} }} }} }}",
+        declaration, argument
+    );
+    let mut args = vec![
+        "api".to_string(),
+        "graphql".to_string(),
+        "-f".to_string(),
+        format!("query={}", query),
+        "-f".to_string(),
+        format!("owner={}", owner),
+        "-f".to_string(),
+        format!("repo={}", repo),
+        "-F".to_string(),
+        format!("first={}", first),
+    ];
+    if let Some(category_id) = category_id {
+        args.push("-f".to_string());
+        args.push(format!("categoryId={}", category_id));
+    }
+    args
+}
+
+/// SSH public key types GitHub accepts
+const SSH_KEY_TYPES: &[&str] = &[
+    "ssh-ed25519",
+    "ssh-rsa",
+    "ecdsa-sha2-nistp256",
+    "ecdsa-sha2-nistp384",
+    "ecdsa-sha2-nistp521",
+    "sk-ssh-ed25519@openssh.com",
+    "sk-ecdsa-sha2-nistp256@openssh.com",
+];
+
+/// Check that a key looks like an SSH public key (`<type> <base64> [comment]`), returning it trimmed.
+/// Errors never include the key itself
+fn validate_ssh_public_key(key: &str) -> Result<&str, McpError> {
+    let key = key.trim();
+    let mut parts = key.split_whitespace();
+    let key_type = parts.next().unwrap_or_default();
+    if !SSH_KEY_TYPES.contains(&key_type) {
+        return Err(McpError::invalid_params(
+            "Not an SSH public key: expected it to start with a key type such as ssh-ed25519 or ssh-rsa (was a private key passed?)",
+            Some(json!({"accepted_types": SSH_KEY_TYPES})),
+        ));
+    }
+    let body = parts.next().unwrap_or_default();
+    let data = body.trim_end_matches('=');
+    let is_base64 = body.len().is_multiple_of(4)
+        && body.len() - data.len() <= 2
+        && data.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'+' || b == b'/');
+    if body.len() < 32 || !is_base64 {
+        return Err(McpError::invalid_params(
+            format!("Not an SSH public key: the data after {} is not valid base64", key_type),
+            None,
+        ));
+    }
+    Ok(key)
+}
+
+/// Check that a key is an ASCII-armored GPG public key block, returning it trimmed.
+/// Errors never include the key itself
+fn validate_gpg_public_key(key: &str) -> Result<&str, McpError> {
+    let key = key.trim();
+    if key.contains("PRIVATE KEY BLOCK") {
+        return Err(McpError::invalid_params(
+            "This is a GPG private key; pass the public key from `gpg --armor --export <key-id>`",
+            None,
+        ));
+    }
+    if !key.starts_with("-----BEGIN PGP PUBLIC KEY BLOCK-----") || !key.ends_with("-----END PGP PUBLIC KEY BLOCK-----") {
+        return Err(McpError::invalid_params(
+            "Not a GPG public key: expected an ASCII-armored block from `gpg --armor --export <key-id>`",
+            None,
+        ));
+    }
+    Ok(key)
+}
+
+/// Build an MCP error for a failed key upload, explaining keys that are already registered
+fn key_command_error(message: &'static str, result: &CommandResult) -> McpError {
+    let error = result.error.as_deref().unwrap_or_default().to_lowercase();
+    if error.contains("already in use") || error.contains("already exists") {
+        return McpError::invalid_params(
+            format!("{}: the key is already registered on GitHub, as a deploy key or on an account, and each key can only be used once; generate a new key pair", message),
+            Some(command_error_data(result)),
+        );
+    }
+    command_error(message, result)
+}
+
+/// Keep the fields of a repository webhook worth returning, leaving out its secret
+fn shape_webhook(hook: &Value) -> Value {
+    json!({
+        "id": hook["id"],
+        "type": hook["type"],
+        "url": hook["config"]["url"],
+        "content_type": hook["config"]["content_type"],
+        "has_secret": hook["config"]["secret"].is_string(),
+        "events": hook["events"],
+        "active": hook["active"],
+        "created_at": hook["created_at"],
+        "ping_url": hook["ping_url"],
+    })
+}
+
+/// Check the delivery URL and events of a new webhook
+fn validate_webhook(url: &str, events: &[String]) -> Result<(), McpError> {
+    if !url.starts_with("https://") {
+        return Err(McpError::invalid_params(
+            format!("Webhook URL {} must use https://", url),
+            None,
+        ));
+    }
+    if events.is_empty() {
+        return Err(McpError::invalid_params(
+            "Set at least one event, e.g. push, or * for all events",
+            None,
+        ));
+    }
+    Ok(())
+}
+
+/// Keep the fields of a listed user worth returning
+fn shape_listed_user(user: &Value) -> Value {
+    json!({
+        "login": user["login"],
+        "type": user["type"],
+        "url": user["html_url"],
+    })
+}
+
+/// Audit log events fetched per request
+const AUDIT_LOG_PAGE_SIZE: u32 = 100;
+
+/// Build the audit log search phrase from the individual filters
+fn audit_log_phrase(param: &OrgAuditLogParam) -> Result<String, McpError> {
+    let mut terms: Vec<String> = param.phrase.iter().cloned().collect();
+    if let Some(action) = &param.action {
+        terms.push(format!("action:{}", action));
+    }
+    if let Some(actor) = &param.actor {
+        terms.push(format!("actor:{}", actor));
+    }
+    for date in [&param.since, &param.until].into_iter().flatten() {
+        if NaiveDate::parse_from_str(date, "%Y-%m-%d").is_err() {
+            return Err(McpError::invalid_params(
+                format!("Invalid date {}, expected YYYY-MM-DD", date),
+                None,
+            ));
+        }
+    }
+    match (&param.since, &param.until) {
+        (Some(since), Some(until)) => terms.push(format!("created:{}..{}", since, until)),
+        (Some(since), None) => terms.push(format!("created:>={}", since)),
+        (None, Some(until)) => terms.push(format!("created:<={}", until)),
+        (None, None) => {}
+    }
+    Ok(terms.join(" "))
+}
+
+/// Normalize an audit log event, keeping the raw event under `detail`
+fn shape_audit_event(event: &Value) -> Value {
+    let timestamp = event["@timestamp"]
+        .as_i64()
+        .and_then(DateTime::from_timestamp_millis)
+        .map(|time| time.to_rfc3339_opts(SecondsFormat::Secs, true));
+    // Events name their subject after its kind; the repository is the most common
+    let target = ["repo", "team", "user", "name", "org"]
+        .iter()
+        .map(|key| &event[key])
+        .find(|value| value.is_string())
+        .cloned()
+        .unwrap_or(Value::Null);
+    json!({
+        "timestamp": timestamp,
+        "actor": event["actor"],
+        "action": event["action"],
+        "target": target,
+        "detail": event,
+    })
+}
+
+/// Build an MCP error for a failed audit log query, explaining its requirements
+fn audit_log_error(org: &str, result: &CommandResult) -> McpError {
+    if is_forbidden(result) || classify_error(result) == ErrorKind::NotFound {
+        return McpError::invalid_request(
+            format!(
+                "Cannot read the audit log of {}: it requires GitHub Enterprise Cloud and an organization owner token with the read:audit_log scope (gh auth refresh -s read:audit_log)",
+                org
+            ),
+            Some(command_error_data(result)),
+        );
+    }
+    command_error("Failed to get audit log", result)
+}
+
+/// Translate a REST API URL of an issue, pull request or commit into its web URL
+fn api_url_to_web(url: &str) -> Option<String> {
+    let (web_base, path) = match url.strip_prefix("https://api.github.com/") {
+        Some(path) => ("https://github.com", path),
+        // GitHub Enterprise Server serves the API under /api/v3 on the web host
+        None => url.split_once("/api/v3/")?,
+    };
+    let mut segments = path.strip_prefix("repos/")?.splitn(4, '/');
+    let (owner, repo, kind, rest) = (segments.next()?, segments.next()?, segments.next()?, segments.next()?);
+    let kind = match kind {
+        "issues" => "issues",
+        "pulls" => "pull",
+        "commits" => "commit",
+        _ => return None,
+    };
+    Some(format!("{}/{}/{}/{}/{}", web_base, owner, repo, kind, rest))
+}
+
+/// Flatten a notification thread, resolving the web URL of its subject
+fn shape_notification(thread: &Value) -> Value {
+    let subject = &thread["subject"];
+    let repository = &thread["repository"];
+    let repo_url = repository["html_url"].as_str().unwrap_or_default();
+    // Releases, discussions and check suites have no translatable API URL; link their repository page instead
+    let url = subject["url"].as_str().and_then(api_url_to_web).unwrap_or_else(|| {
+        match subject["type"].as_str() {
+            Some("Release") => format!("{}/releases", repo_url),
+            Some("Discussion") => format!("{}/discussions", repo_url),
+            Some("CheckSuite") => format!("{}/actions", repo_url),
+            _ => repo_url.to_string(),
+        }
+    });
+    json!({
+        "id": thread["id"],
+        "repository": repository["full_name"],
+        "title": subject["title"],
+        "type": subject["type"],
+        "reason": thread["reason"],
+        "unread": thread["unread"],
+        "updated_at": thread["updated_at"],
+        "url": url,
+    })
+}
+
+/// Pull request number and title a commit message refers to, from a merge commit
+/// ("Merge pull request #12 from ...", title on a later line) or a squash merge ("Title (#12)")
+fn pull_request_of_commit(message: &str) -> Option<(u64, String)> {
+    let mut lines = message.lines();
+    let first = lines.next()?.trim();
+    if let Some(rest) = first.strip_prefix("Merge pull request #") {
+        let number = rest.split_whitespace().next()?.parse().ok()?;
+        let title = lines.map(str::trim).find(|line| !line.is_empty()).unwrap_or(first);
+        return Some((number, title.to_string()));
+    }
+    let (title, number) = first.strip_suffix(')')?.rsplit_once("(#")?;
+    Some((number.parse().ok()?, title.trim_end().to_string()))
+}
+
+/// Build `gh api` arguments for a REST search endpoint
+fn search_api_args(endpoint: &str, query: String, limit: Option<u32>) -> Vec<String> {
+    vec![
+        "api".to_string(),
+        "--method".to_string(),
+        "GET".to_string(),
+        endpoint.to_string(),
+        "-f".to_string(),
+        format!("q={}", query),
+        "-F".to_string(),
+        format!("per_page={}", limit.unwrap_or(30).min(100)),
+    ]
+}
+
+/// Common words ignored when extracting issue title keywords
+const STOPWORDS: [&str; 40] = [
+    "the", "and", "for", "with", "when", "not", "are", "but", "can", "cannot", "does", "doesn",
+    "from", "has", "have", "how", "into", "its", "should", "that", "this", "was", "what", "why",
+    "will", "won", "would", "you", "your", "after", "before", "error", "issue", "bug", "fails",
+    "failing", "using", "use", "able", "any",
+];
+
+/// GraphQL mutation pinning an issue to its repository
+const PIN_ISSUE_MUTATION: &str = "mutation($id: ID!) { pinIssue(input: {issueId: $id}) { issue { number } } }";
+
+/// GraphQL mutation unpinning an issue
+const UNPIN_ISSUE_MUTATION: &str = "mutation($id: ID!) { unpinIssue(input: {issueId: $id}) { issue { number } } }";
+
+/// Most search operators allowed in one query, so at most this many plus one keywords are ORed
+const MAX_SEARCH_OPERATORS: usize = 5;
+
+/// Distinct lowercase words of a title without stopwords or words shorter than three characters
+fn title_keywords(title: &str) -> Vec<String> {
+    let mut keywords: Vec<String> = Vec::new();
+    for word in title.split(|c: char| !c.is_alphanumeric()).map(str::to_lowercase) {
+        if word.chars().count() >= 3 && !STOPWORDS.contains(&word.as_str()) && !keywords.contains(&word) {
+            keywords.push(word);
+        }
+    }
+    keywords
+}
+
+/// `--json` fields accepted by `gh repo list` and `gh repo view`
+const REPO_JSON_FIELDS: &[&str] = &[
+    "createdAt", "defaultBranchRef", "description", "diskUsage", "forkCount", "homepageUrl", "id",
+    "isArchived", "isFork", "isPrivate", "isTemplate", "licenseInfo", "name", "nameWithOwner", "owner",
+    "primaryLanguage", "pushedAt", "repositoryTopics", "stargazerCount", "updatedAt", "url", "visibility",
+    "watchers",
+];
+
+/// `--json` fields accepted by `gh issue list`
+const ISSUE_JSON_FIELDS: &[&str] = &[
+    "assignees", "author", "body", "closed", "closedAt", "comments", "createdAt", "id", "isPinned",
+    "labels", "milestone", "number", "reactionGroups", "state", "stateReason", "title", "updatedAt", "url",
+];
+
+/// `--json` fields accepted by `gh pr list`
+const PR_JSON_FIELDS: &[&str] = &[
+    "additions", "assignees", "author", "baseRefName", "body", "changedFiles", "closed", "closedAt",
+    "comments", "createdAt", "deletions", "headRefName", "headRepository", "headRepositoryOwner", "id",
+    "isCrossRepository", "isDraft", "labels", "mergeStateStatus", "mergeable", "mergedAt", "mergedBy",
+    "milestone", "number", "reviewDecision", "reviewRequests", "state", "statusCheckRollup", "title",
+    "updatedAt", "url",
+];
+
+/// Build the `--json` field list from requested fields, checked against an allowlist, or the default
+fn json_fields(requested: Option<Vec<String>>, default: &str, allowed: &[&str]) -> Result<String, McpError> {
+    let Some(requested) = requested.filter(|fields| !fields.is_empty()) else {
+        return Ok(default.to_string());
+    };
+    let unknown: Vec<&String> = requested.iter().filter(|field| !allowed.contains(&field.as_str())).collect();
+    if !unknown.is_empty() {
+        return Err(McpError::invalid_params(
+            format!("Unknown fields: {}", unknown.iter().map(|f| f.as_str()).collect::<Vec<_>>().join(", ")),
+            Some(json!({"unknown": unknown, "allowed": allowed})),
+        ));
+    }
+    Ok(requested.join(","))
+}
+
+/// Parse JSON returned by a GitHub CLI command
+fn parse_json_output(output: &str) -> Result<Value, McpError> {
+    serde_json::from_str(output).map_err(|e| {
+        McpError::internal_error(
+            "Failed to parse GitHub CLI output",
+            Some(json!({"error": e.to_string()})),
+        )
+    })
+}
+
+/// Build the commit search query string with qualifiers
+fn build_commit_query(param: &SearchCommitsParam) -> String {
+    let mut query = param.query.clone();
+    let qualifiers = [
+        ("author", &param.author),
+        ("committer", &param.committer),
+        ("repo", &param.repo),
+        ("user", &param.owner),
+        ("hash", &param.hash),
+    ];
+    for (name, value) in qualifiers {
+        if let Some(value) = value {
+            query.push_str(&format!(" {}:{}", name, value));
+        }
+    }
+    match (&param.author_date_from, &param.author_date_to) {
+        (Some(from), Some(to)) => query.push_str(&format!(" author-date:{}..{}", from, to)),
+        (Some(from), None) => query.push_str(&format!(" author-date:>={}", from)),
+        (None, Some(to)) => query.push_str(&format!(" author-date:<={}", to)),
+        (None, None) => {}
+    }
+    if let Some(merge) = param.merge {
+        query.push_str(&format!(" merge:{}", merge));
+    }
+    query
+}
+
+/// GraphQL query used by user search, which unlike REST search returns display names
+const SEARCH_USERS_QUERY: &str = "query($q: String!, $first: Int!) { search(query: $q, type: USER, first: $first) { userCount nodes { __typename ... on User { login name url } ... on Organization { login name url } } } }";
+
+/// Build the user search query string with qualifiers
+fn build_user_query(param: &SearchUsersParam) -> String {
+    let mut query = param.query.clone();
+    match param.account_type {
+        Some(AccountType::User) => query.push_str(" type:user"),
+        Some(AccountType::Org) => query.push_str(" type:org"),
+        None => {}
+    }
+    let qualifiers = [
+        ("location", &param.location),
+        ("language", &param.language),
+        ("followers", &param.followers),
+    ];
+    for (name, value) in qualifiers {
+        if let Some(value) = value {
+            query.push_str(&format!(" {}:{}", name, value));
+        }
+    }
+    query
+}
+
+/// Reject filters that do not apply to the chosen search kind
+fn validate_search_filters(kind: SearchKind, filters: &SearchFilters) -> Result<(), McpError> {
+    let allowed = kind.allowed_filters();
+    let set = serde_json::to_value(filters).unwrap_or_default();
+    let invalid: Vec<&String> = set
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter(|(name, value)| !value.is_null() && !allowed.contains(&name.as_str()))
+        .map(|(name, _)| name)
+        .collect();
+    if invalid.is_empty() {
+        return Ok(());
+    }
+    Err(McpError::invalid_params(
+        format!(
+            "Filters not applicable to {} search: {}",
+            serde_json::to_value(kind).unwrap_or_default().as_str().unwrap_or_default(),
+            invalid.iter().map(|name| name.as_str()).collect::<Vec<_>>().join(", ")
+        ),
+        Some(json!({"invalid_fields": invalid, "allowed_fields": allowed})),
+    ))
+}
+
+/// Build the repository search query string with qualifiers
+fn build_repo_query(param: &SearchReposParam) -> String {
+    let mut query = param.query.clone();
+    let qualifiers = [
+        ("user", &param.owner),
+        ("language", &param.language),
+        ("topic", &param.topic),
+        ("stars", &param.stars),
+    ];
+    for (name, value) in qualifiers {
+        if let Some(value) = value {
+            query.push_str(&format!(" {}:{}", name, value));
+        }
+    }
+    query
+}
+
+/// Build the issue or pull request search query string with qualifiers
+fn build_issue_query(param: &SearchIssuesParam, pull_requests: bool) -> String {
+    let mut query = param.query.clone();
+    query.push_str(if pull_requests { " is:pr" } else { " is:issue" });
+    let qualifiers = [
+        ("repo", &param.repo),
+        ("user", &param.owner),
+        ("author", &param.author),
+        ("label", &param.label),
+    ];
+    for (name, value) in qualifiers {
+        if let Some(value) = value {
+            query.push_str(&format!(" {}:{}", name, value));
+        }
+    }
+    match param.state {
+        Some(IssueState::Open) => query.push_str(" state:open"),
+        Some(IssueState::Closed) => query.push_str(" state:closed"),
+        None => {}
+    }
+    query
+}
+
+/// Build the code search query string with qualifiers
+fn build_code_query(param: &SearchCodeParam) -> String {
+    let mut query = param.query.clone();
+    let qualifiers = [
+        ("repo", &param.repo),
+        ("user", &param.owner),
+        ("language", &param.language),
+        ("filename", &param.filename),
+        ("extension", &param.extension),
+        ("path", &param.path),
+    ];
+    for (name, value) in qualifiers {
+        if let Some(value) = value {
+            query.push_str(&format!(" {}:{}", name, value));
+        }
+    }
+    match param.match_in {
+        Some(CodeMatchIn::File) => query.push_str(" in:file"),
+        Some(CodeMatchIn::Path) => query.push_str(" in:path"),
+        None => {}
+    }
+    query
+}
+
+#[tool(tool_box)]
+impl GitHubService {
+    pub fn new(config: &Config) -> Result<Self> {
+        let version = check_gh_installed(&config.gh_binary)?;
+        tracing::info!("Using GitHub CLI {} ({})", version, config.gh_binary);
+        let cache = NonZeroUsize::new(config.cache_capacity)
+            .map(|capacity| Arc::new(Cache::new(capacity, config.cache_ttl)));
+        if config.dry_run {
+            tracing::info!("Dry-run mode enabled, gh commands will be logged but not executed");
+        }
+        let audit = match &config.audit_log {
+            Some(path) => {
+                let logger = AuditLogger::open(path)
+                    .with_context(|| format!("Failed to open audit log {}", path.display()))?;
+                tracing::info!("Writing audit log to {}", path.display());
+                Some(Arc::new(logger))
+            }
+            None => None,
+        };
+        Ok(Self {
+            last_result: Arc::new(Mutex::new(None)),
+            cache,
+            dry_run: config.dry_run,
+            audit,
+            retry_attempts: config.retry_attempts,
+            retry_base_delay: config.retry_base_delay,
+            allow_codespace_exec: config.allow_codespace_exec,
+            gh_binary: Arc::from(config.gh_binary.as_str()),
+            gh_supports_slurp: gh_major_minor(&version) >= SLURP_GH_VERSION,
+            current_user: Arc::new(Mutex::new(None)),
+            in_flight: Arc::new(RwLock::new(())),
+            gh_permits: Arc::new(Semaphore::new(config.max_concurrent.max(1))),
+            metrics: Arc::new(Metrics::new()),
+            rate_limits: Arc::new(Mutex::new(HashMap::new())),
+        })
+    }
+
+    /// Star a repository
+    #[tool(description = "Star a repository as the current user")]
+    async fn star_repo(
+        &self,
+        #[tool(aggr)] param: RepoParam,
+    ) -> Result<CallToolResult, McpError> {
+        let args = vec![
+            "api".to_string(),
+            "--method".to_string(),
+            "PUT".to_string(),
+            format!("user/starred/{}/{}", param.owner, param.repo),
+        ];
+        let result = self.run_gh_command(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        // GitHub answers 204 No Content, so empty output is the expected success
+        if result.success {
+            Ok(command_success(format!("Starred {}/{}", param.owner, param.repo), &result))
+        } else {
+            Err(command_error("Failed to star repository", &result))
+        }
+    }
+
+    /// Unstar a repository
+    #[tool(description = "Remove the current user's star from a repository")]
+    async fn unstar_repo(
+        &self,
+        #[tool(aggr)] param: RepoParam,
+    ) -> Result<CallToolResult, McpError> {
+        let args = vec![
+            "api".to_string(),
+            "--method".to_string(),
+            "DELETE".to_string(),
+            format!("user/starred/{}/{}", param.owner, param.repo),
+        ];
+        let result = self.run_gh_command(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if result.success {
+            Ok(command_success(format!("Unstarred {}/{}", param.owner, param.repo), &result))
+        } else {
+            Err(command_error("Failed to unstar repository", &result))
+        }
+    }
+
+    /// Check whether the current user starred a repository
+    #[tool(description = "Check whether the current user has starred a repository")]
+    async fn is_starred(
+        &self,
+        #[tool(aggr)] param: RepoParam,
+    ) -> Result<CallToolResult, McpError> {
+        let args = vec!["api".to_string(), format!("user/starred/{}/{}", param.owner, param.repo)];
+        let result = self.run_gh_read(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        // 204 means starred, 404 means not starred
+        let starred = if result.success {
+            true
+        } else if classify_error(&result) == ErrorKind::NotFound {
+            false
+        } else {
+            return Err(command_error("Failed to check star", &result));
+        };
+        let summary = json!({"repo": format!("{}/{}", param.owner, param.repo), "starred": starred});
+        Ok(CallToolResult::success(vec![Content::text(summary.to_string())]))
+    }
+
+    /// List notifications of the current user
+    #[tool(description = "List notifications of the current user, unread only unless all is set, with thread id, repository, subject title and type (Issue, PullRequest, Release...), reason, unread flag, updated_at and the subject's web URL. Filter by participation, date, repository and reason")]
+    async fn list_notifications(
+        &self,
+        #[tool(aggr)] param: ListNotificationsParam,
+    ) -> Result<CallToolResult, McpError> {
+        let path = match &param.repo {
+            Some(repo) => format!("repos/{}/notifications", repo),
+            None => "notifications".to_string(),
+        };
+        let mut query = vec![
+            ("all", param.all.to_string()),
+            ("participating", param.participating.to_string()),
+        ];
+        if let Some(since) = &param.since {
+            query.push(("since", since.clone()));
+        }
+        let result = self.api_list_all(&path, query).await;
+        if !result.success {
+            return Err(command_error("Failed to get notifications", &result));
+        }
+        let mut notifications: Vec<Value> = parse_json_lines(&result.output)?
+            .iter()
+            .filter(|thread| match &param.reason {
+                Some(reason) => thread["reason"].as_str() == Some(reason.as_str()),
+                None => true,
+            })
+            .map(shape_notification)
+            .collect();
+        if let Some(limit) = param.limit {
+            notifications.truncate(limit as usize);
+        }
+        Ok(command_success(Value::from(notifications).to_string(), &result))
+    }
+
+    /// Mark notifications as read
+    #[tool(description = "Mark one notification thread as read by thread_id, or in bulk every notification of a repository updated before a timestamp (default now). The bulk form reports how many unread threads it covers; GitHub may apply it asynchronously")]
+    async fn mark_notification_read(
+        &self,
+        #[tool(aggr)] param: MarkNotificationReadParam,
+    ) -> Result<CallToolResult, McpError> {
+        let repo = match (&param.thread_id, &param.repo) {
+            (Some(thread_id), None) => {
+                let args = vec![
+                    "api".to_string(),
+                    "--method".to_string(),
+                    "PATCH".to_string(),
+                    format!("notifications/threads/{}", thread_id),
+                ];
+                let result = self.run_gh_command(args).await;
+                
+                let mut last_result = self.last_result.lock().await;
+                *last_result = Some(result.clone());
+                
+                return if result.success {
+                    let summary = json!({"thread_id": thread_id, "unread": false});
+                    Ok(command_success(summary.to_string(), &result))
+                } else {
+                    Err(command_error("Failed to mark notification as read", &result))
+                };
+            }
+            (None, Some(repo)) => repo,
+            _ => return Err(McpError::invalid_params("Set either thread_id or repo", None)),
+        };
+        
+        let before = match &param.before {
+            Some(before) => DateTime::parse_from_rfc3339(before)
+                .map_err(|_| McpError::invalid_params(format!("Invalid timestamp {}, expected ISO 8601", before), None))?
+                .with_timezone(&Utc),
+            None => Utc::now(),
+        };
+        // The API only acknowledges bulk requests, so count the affected threads up front
+        let path = format!("repos/{}/notifications", repo);
+        let result = self.api_list_all(&path, vec![("before", before.to_rfc3339_opts(SecondsFormat::Secs, true))]).await;
+        if !result.success {
+            return Err(command_error("Failed to get notifications", &result));
+        }
+        let threads = parse_json_lines(&result.output)?.len();
+        
+        let body = json!({"last_read_at": before.to_rfc3339_opts(SecondsFormat::Secs, true)});
+        let args = vec![
+            "api".to_string(),
+            "--method".to_string(),
+            "PUT".to_string(),
+            path,
+            "--input".to_string(),
+            "-".to_string(),
+        ];
+        let result = self.run_gh_command_with_input(args, body.to_string()).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if result.success {
+            let summary = json!({
+                "repo": repo,
+                "marked_read": threads,
+                "message": "GitHub may mark large batches asynchronously; list_notifications can lag briefly",
+            });
+            Ok(command_success(summary.to_string(), &result))
+        } else {
+            Err(command_error("Failed to mark notifications as read", &result))
+        }
+    }
+
+    /// Mark a notification thread as done
+    #[tool(description = "Mark a notification thread as done, removing it from the inbox")]
+    async fn mark_thread_done(
+        &self,
+        #[tool(aggr)] param: NotificationThreadParam,
+    ) -> Result<CallToolResult, McpError> {
+        let args = vec![
+            "api".to_string(),
+            "--method".to_string(),
+            "DELETE".to_string(),
+            format!("notifications/threads/{}", param.thread_id),
+        ];
+        let result = self.run_gh_command(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if result.success {
+            let summary = json!({"thread_id": param.thread_id, "done": true});
+            Ok(command_success(summary.to_string(), &result))
+        } else if classify_error(&result) == ErrorKind::NotFound {
+            // Older GitHub Enterprise Server versions lack the endpoint
+            Err(McpError::invalid_params(
+                format!("Thread {} not found, or this GitHub host does not support marking threads as done; use mark_notification_read instead", param.thread_id),
+                Some(command_error_data(&result)),
+            ))
+        } else {
+            Err(command_error("Failed to mark notification as done", &result))
+        }
+    }
+
+    /// Subscribe to, ignore or unsubscribe from a notification thread
+    #[tool(description = "Subscribe to, ignore or unsubscribe from a notification thread, by thread_id or by the issue or pull request web URL. ignore mutes the thread completely: no further notifications, even when you are mentioned. unsubscribe returns to the default of notifying only when participating or mentioned")]
+    async fn set_thread_subscription(
+        &self,
+        #[tool(aggr)] param: SetThreadSubscriptionParam,
+    ) -> Result<CallToolResult, McpError> {
+        let thread_id = match (&param.thread_id, &param.url) {
+            (Some(thread_id), None) => thread_id.clone(),
+            (None, Some(url)) => self.notification_thread_for_url(url).await?,
+            _ => return Err(McpError::invalid_params("Set either thread_id or url", None)),
+        };
+        let path = format!("notifications/threads/{}/subscription", thread_id);
+        let args = match param.mode {
+            ThreadSubscriptionMode::Unsubscribe => vec!["api".to_string(), "--method".to_string(), "DELETE".to_string(), path],
+            mode => vec![
+                "api".to_string(),
+                "--method".to_string(),
+                "PUT".to_string(),
+                path,
+                "-F".to_string(),
+                format!("ignored={}", matches!(mode, ThreadSubscriptionMode::Ignore)),
+            ],
+        };
+        let result = self.run_gh_command(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if !result.success {
+            return Err(command_error("Failed to set thread subscription", &result));
+        }
+        let state = match param.mode {
+            ThreadSubscriptionMode::Subscribe => "subscribed",
+            ThreadSubscriptionMode::Ignore => "ignored",
+            ThreadSubscriptionMode::Unsubscribe => "default",
+        };
+        let summary = json!({
+            "thread_id": thread_id,
+            "subscription": state,
+            "muted": matches!(param.mode, ThreadSubscriptionMode::Ignore),
+        });
+        Ok(command_success(summary.to_string(), &result))
+    }
+
+    /// List followers of a user
+    #[tool(description = "List followers of a user (default the current user). Paginated with per_page and page; follow next_page for more")]
+    async fn list_followers(
+        &self,
+        #[tool(aggr)] param: ListFollowsParam,
+    ) -> Result<CallToolResult, McpError> {
+        let path = match &param.username {
+            Some(username) => format!("users/{}/followers", username),
+            None => "user/followers".to_string(),
+        };
+        let (summary, result) = self
+            .api_page(&path, Vec::new(), &param.page, shape_listed_user, "Failed to get followers")
+            .await?;
+        Ok(command_success(summary.to_string(), &result))
+    }
+
+    /// List users a user follows
+    #[tool(description = "List users followed by a user (default the current user). Paginated with per_page and page; follow next_page for more")]
+    async fn list_following(
+        &self,
+        #[tool(aggr)] param: ListFollowsParam,
+    ) -> Result<CallToolResult, McpError> {
+        let path = match &param.username {
+            Some(username) => format!("users/{}/following", username),
+            None => "user/following".to_string(),
+        };
+        let (summary, result) = self
+            .api_page(&path, Vec::new(), &param.page, shape_listed_user, "Failed to get followed users")
+            .await?;
+        Ok(command_success(summary.to_string(), &result))
+    }
+
+    /// Check whether a user follows another
+    #[tool(description = "Check whether a user (default the current user) follows login")]
+    async fn is_following(
+        &self,
+        #[tool(aggr)] param: IsFollowingParam,
+    ) -> Result<CallToolResult, McpError> {
+        let path = match &param.username {
+            Some(username) => format!("users/{}/following/{}", username, param.login),
+            None => format!("user/following/{}", param.login),
+        };
+        let result = self.run_gh_read(vec!["api".to_string(), path]).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        // 204 means following, 404 means not following
+        let following = if result.success {
+            true
+        } else if classify_error(&result) == ErrorKind::NotFound {
+            false
+        } else {
+            return Err(command_error("Failed to check follow", &result));
+        };
+        let summary = json!({"login": param.login, "following": following});
+        Ok(CallToolResult::success(vec![Content::text(summary.to_string())]))
+    }
+
+    /// Follow a user
+    #[tool(description = "Follow a user as the current user")]
+    async fn follow_user(
+        &self,
+        #[tool(aggr)] param: LoginParam,
+    ) -> Result<CallToolResult, McpError> {
+        let args = vec![
+            "api".to_string(),
+            "--method".to_string(),
+            "PUT".to_string(),
+            format!("user/following/{}", param.login),
+        ];
+        let result = self.run_gh_command(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if result.success {
+            let summary = json!({"login": param.login, "following": true});
+            Ok(command_success(summary.to_string(), &result))
+        } else {
+            Err(command_error("Failed to follow user", &result))
+        }
+    }
+
+    /// Unfollow a user
+    #[tool(description = "Stop following a user as the current user")]
+    async fn unfollow_user(
+        &self,
+        #[tool(aggr)] param: LoginParam,
+    ) -> Result<CallToolResult, McpError> {
+        let args = vec![
+            "api".to_string(),
+            "--method".to_string(),
+            "DELETE".to_string(),
+            format!("user/following/{}", param.login),
+        ];
+        let result = self.run_gh_command(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if result.success {
+            let summary = json!({"login": param.login, "following": false});
+            Ok(command_success(summary.to_string(), &result))
+        } else {
+            Err(command_error("Failed to unfollow user", &result))
+        }
+    }
+
+    /// List starred repositories
+    #[tool(description = "List repositories starred by a user (default the current user), most recently starred first, optionally filtered by language. Paginated with per_page and page; follow next_page for more")]
+    async fn list_starred(
+        &self,
+        #[tool(aggr)] param: ListStarredParam,
+    ) -> Result<CallToolResult, McpError> {
+        let path = match &param.username {
+            Some(username) => format!("users/{}/starred", username),
+            None => "user/starred".to_string(),
+        };
+        let (mut summary, result) = self
+            .api_page(&path, Vec::new(), &param.page, shape_listed_repo, "Failed to get starred repositories")
+            .await?;
+        if let (Some(language), Some(items)) = (&param.language, summary["items"].as_array_mut()) {
+            items.retain(|item| item["language"].as_str().is_some_and(|l| l.eq_ignore_ascii_case(language)));
+        }
+        Ok(command_success(summary.to_string(), &result))
+    }
+
+    /// Watch, ignore or unwatch a repository
+    #[tool(description = "Set the current user's notification subscription to a repository: watch (all activity), ignore (no notifications at all) or unwatch (back to the default of participating and mentions only)")]
+    async fn set_repo_subscription(
+        &self,
+        #[tool(aggr)] param: SetRepoSubscriptionParam,
+    ) -> Result<CallToolResult, McpError> {
+        let path = format!("repos/{}/{}/subscription", param.owner, param.repo);
+        let args = match param.mode {
+            SubscriptionMode::Unwatch => vec!["api".to_string(), "--method".to_string(), "DELETE".to_string(), path],
+            mode => {
+                let watch = matches!(mode, SubscriptionMode::Watch);
+                vec![
+                    "api".to_string(),
+                    "--method".to_string(),
+                    "PUT".to_string(),
+                    path,
+                    "-F".to_string(),
+                    format!("subscribed={}", watch),
+                    "-F".to_string(),
+                    format!("ignored={}", !watch),
+                ]
+            }
+        };
+        let result = self.run_gh_command(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if !result.success {
+            return Err(command_error("Failed to set repository subscription", &result));
+        }
+        let state = match param.mode {
+            SubscriptionMode::Watch => "watching",
+            SubscriptionMode::Ignore => "ignoring",
+            SubscriptionMode::Unwatch => "default",
+        };
+        let summary = json!({"repo": format!("{}/{}", param.owner, param.repo), "subscription": state});
+        Ok(command_success(summary.to_string(), &result))
+    }
+
+    /// Get the current user's subscription to a repository
+    #[tool(description = "Get the current user's notification subscription to a repository: watching, ignoring or default (participating and mentions only)")]
+    async fn get_repo_subscription(
+        &self,
+        #[tool(aggr)] param: RepoParam,
+    ) -> Result<CallToolResult, McpError> {
+        let args = vec!["api".to_string(), format!("repos/{}/{}/subscription", param.owner, param.repo)];
+        let result = self.run_gh_read(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        // 404 means no explicit subscription, i.e. the default
+        let state = if result.success {
+            let subscription = parse_json_output(&result.output)?;
+            if subscription["ignored"] == true {
+                "ignoring"
+            } else if subscription["subscribed"] == true {
+                "watching"
+            } else {
+                "default"
+            }
+        } else if classify_error(&result) == ErrorKind::NotFound {
+            "default"
+        } else {
+            return Err(command_error("Failed to get repository subscription", &result));
+        };
+        let summary = json!({"repo": format!("{}/{}", param.owner, param.repo), "subscription": state});
+        Ok(CallToolResult::success(vec![Content::text(summary.to_string())]))
+    }
+
+    /// List repositories the current user watches
+    #[tool(description = "List repositories the current user watches. Paginated with per_page and page; follow next_page for more")]
+    async fn list_watched(
+        &self,
+        #[tool(aggr)] param: ListWatchedParam,
+    ) -> Result<CallToolResult, McpError> {
+        let (summary, result) = self
+            .api_page("user/subscriptions", Vec::new(), &param.page, shape_listed_repo, "Failed to get watched repositories")
+            .await?;
+        Ok(command_success(summary.to_string(), &result))
+    }
+
+    /// List repositories of current user
+    #[tool(description = "List repositories of current user. Set fields to choose the returned JSON fields")]
+    async fn list_repos(
+        &self,
+        #[tool(aggr)] param: ListReposParam,
+    ) -> Result<CallToolResult, McpError> {
+        let fields = json_fields(param.fields, "name,description,url", REPO_JSON_FIELDS)?;
+        let args = vec!["repo".to_string(), "list".to_string(), "--json".to_string(), fields];
+        let result = self.run_cached(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if result.success {
+            Ok(command_success(result.output.clone(), &result))
+        } else {
+            Err(command_error("Failed to get repository list", &result))
+        }
+    }
+
+    /// Get repository information
+    #[tool(description = "Get information of specified repository. Set fields to choose the returned JSON fields")]
+    async fn repo_view(
+        &self,
+        #[tool(aggr)] param: RepoViewParam,
+    ) -> Result<CallToolResult, McpError> {
+        let repo = format!("{}/{}", param.owner, param.repo);
+        let fields = json_fields(param.fields, "name,description,url,stargazerCount,forkCount,watchers", REPO_JSON_FIELDS)?;
+        let args = vec!["repo".to_string(), "view".to_string(), repo, "--json".to_string(), fields];
+        let result = self.run_cached(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if result.success {
+            Ok(command_success(result.output.clone(), &result))
+        } else {
+            Err(command_error("Failed to get repository information", &result))
+        }
+    }
+
+    /// List issues of specified repository
+    #[tool(description = "List issues of specified repository. Set fields to choose the returned JSON fields")]
+    async fn list_issues(
+        &self,
+        #[tool(aggr)] param: ListIssuesParam,
+    ) -> Result<CallToolResult, McpError> {
+        let repo = format!("{}/{}", param.owner, param.repo);
+        let fields = json_fields(param.fields, "number,title,state,url", ISSUE_JSON_FIELDS)?;
+        let args = vec!["issue".to_string(), "list".to_string(), "--repo".to_string(), repo, "--json".to_string(), fields];
+        let result = self.run_gh_read(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if result.success {
+            Ok(command_success(result.output.clone(), &result))
+        } else {
+            Err(command_error("Failed to get issues list", &result))
+        }
+    }
+
+    /// Create issue
+    #[tool(description = "Create issue in specified repository, or in the repository of the working directory when repo is omitted. Returns the repository the issue was created in")]
+    async fn create_issue(
+        &self,
+        #[tool(aggr)] param: CreateIssueParam,
+    ) -> Result<CallToolResult, McpError> {
+        let repo = match param.repo {
+            Some(repo) => repo,
+            None => self.resolve_current_repo().await?,
+        };
+        let mut args = vec!["issue".to_string(), "create".to_string()];
+        
+        args.push("--repo".to_string());
+        args.push(repo.clone());
+        
+        args.push("--title".to_string());
+        args.push(param.title);
+        
+        if let Some(body) = param.body {
+            args.push("--body".to_string());
+            args.push(body);
+        }
+        
+        let result = self.run_gh_command(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if result.success {
+            let created = json!({"repo": repo, "url": result.output.trim()});
+            Ok(command_success(created.to_string(), &result))
+        } else {
+            Err(command_error("Failed to create issue", &result))
+        }
+    }
+
+    /// List issue and pull request templates of a repository
+    #[tool(description = "List the issue templates in .github/ISSUE_TEMPLATE and pull request templates (.github/PULL_REQUEST_TEMPLATE*) of a repository with kind, name and path. Empty when the repository has none")]
+    async fn list_templates(
+        &self,
+        #[tool(aggr)] param: RepoParam,
+    ) -> Result<CallToolResult, McpError> {
+        let repo = format!("{}/{}", param.owner, param.repo);
+        let mut templates = Vec::new();
+        for entry in self.list_contents(&repo, ".github").await? {
+            let name = entry["name"].as_str().unwrap_or_default();
+            let path = entry["path"].as_str().unwrap_or_default();
+            let is_dir = entry["type"] == "dir";
+            let kind = if name == "ISSUE_TEMPLATE" && is_dir {
+                "issue"
+            } else if name.to_uppercase().starts_with("PULL_REQUEST_TEMPLATE") {
+                "pull_request"
+            } else {
+                continue;
+            };
+            let files = if is_dir {
+                self.list_contents(&repo, path).await?
+            } else {
+                vec![entry.clone()]
+            };
+            for file in files.iter().filter(|file| file["type"] == "file") {
+                // config.yml configures the template chooser and is not a template
+                if kind == "issue" && matches!(file["name"].as_str(), Some("config.yml" | "config.yaml")) {
+                    continue;
+                }
+                templates.push(json!({"kind": kind, "name": file["name"], "path": file["path"]}));
+            }
+        }
+        Ok(CallToolResult::success(vec![Content::text(Value::from(templates).to_string())]))
+    }
+
+    /// Create an issue from a repository issue template
+    #[tool(description = "Create an issue from a Markdown issue template in .github/ISSUE_TEMPLATE, filling its {{placeholder}}s from fields. Title and labels default to the template's front matter. Errors list any placeholders without a value")]
+    async fn create_issue_from_template(
+        &self,
+        #[tool(aggr)] param: CreateIssueFromTemplateParam,
+    ) -> Result<CallToolResult, McpError> {
+        if param.template.ends_with(".yml") || param.template.ends_with(".yaml") {
+            return Err(McpError::invalid_params(
+                "Issue forms (.yml) are not supported, use a Markdown template",
+                Some(json!({"template": param.template})),
+            ));
+        }
+        let repo = format!("{}/{}", param.owner, param.repo);
+        let args = vec![
+            "api".to_string(),
+            format!("repos/{}/contents/.github/ISSUE_TEMPLATE/{}", repo, percent_encode(&param.template)),
+            "--header".to_string(),
+            "Accept: application/vnd.github.raw".to_string(),
+        ];
+        let result = self.run_gh_read(args).await;
+        if !result.success {
+            let mut last_result = self.last_result.lock().await;
+            *last_result = Some(result.clone());
+            return Err(command_error("Failed to get issue template", &result));
+        }
+        
+        let (front_matter, template) = split_front_matter(&result.output);
+        let title = param
+            .title
+            .or_else(|| front_matter.get("title").cloned())
+            .unwrap_or_default();
+        let rendered = render_template(&title, &param.fields)
+            .and_then(|title| render_template(template, &param.fields).map(|body| (title, body)));
+        let (title, body) = rendered.map_err(|missing| {
+            McpError::invalid_params(
+                format!("Missing template fields: {}", missing.join(", ")),
+                Some(json!({"missing": missing})),
+            )
+        })?;
+        if title.trim().is_empty() {
+            return Err(McpError::invalid_params("The template has no title, set title", None));
+        }
+        
+        let mut args = vec![
+            "issue".to_string(),
+            "create".to_string(),
+            "--repo".to_string(),
+            repo.clone(),
+            "--title".to_string(),
+            title,
+            "--body-file".to_string(),
+            "-".to_string(),
+        ];
+        for label in front_matter.get("labels").map(|labels| front_matter_list(labels)).unwrap_or_default() {
+            args.push("--label".to_string());
+            args.push(label);
+        }
+        let result = self.run_gh_command_with_input(args, body).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if result.success {
+            let created = json!({"repo": repo, "url": result.output.trim()});
+            Ok(command_success(created.to_string(), &result))
+        } else {
+            Err(command_error("Failed to create issue", &result))
+        }
+    }
+
+    /// List the timeline events of an issue
+    #[tool(description = "List the full event history of an issue or pull request: comments, label changes, assignments, references, renames, closes and more, each with actor, time and details")]
+    async fn list_issue_timeline_events(
+        &self,
+        #[tool(aggr)] param: ListIssueTimelineParam,
+    ) -> Result<CallToolResult, McpError> {
+        let args = vec![
+            "api".to_string(),
+            "--paginate".to_string(),
+            format!("repos/{}/{}/issues/{}/timeline", param.owner, param.repo, param.number),
+            "--jq".to_string(),
+            ".[]".to_string(),
+        ];
+        let result = self.run_gh_read(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if !result.success {
+            return Err(command_error("Failed to get issue timeline", &result));
+        }
+        
+        let events: Vec<Value> = parse_json_lines(&result.output)?.iter().map(shape_timeline_event).collect();
+        Ok(command_success(Value::from(events).to_string(), &result))
+    }
+
+    /// Find existing issues similar to a new issue title
+    #[tool(description = "Find existing issues in a repository that look like duplicates of a new issue title. Searches by the title's keywords and returns number, title, state, URL, the matched keywords and a 0-1 similarity, best first")]
+    async fn find_similar_issues(
+        &self,
+        #[tool(aggr)] param: FindSimilarIssuesParam,
+    ) -> Result<CallToolResult, McpError> {
+        let keywords = title_keywords(&param.title);
+        if keywords.is_empty() {
+            return Err(McpError::invalid_params(
+                "title has no keywords to search for",
+                Some(json!({"title": param.title})),
+            ));
+        }
+        // Any keyword may match; ranking by shared keywords happens below
+        let terms: Vec<&str> = keywords.iter().take(MAX_SEARCH_OPERATORS + 1).map(String::as_str).collect();
+        let query = format!("repo:{}/{} is:issue {}", param.owner, param.repo, terms.join(" OR "));
+        let args = search_api_args("search/issues", query, Some(30));
+        let result = self.run_gh_read(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if !result.success {
+            return Err(command_error("Failed to search issues", &result));
+        }
+        
+        let body = parse_json_output(&result.output)?;
+        let mut matches: Vec<Value> = body["items"]
+            .as_array()
+            .map(|items| {
+                items
+                    .iter()
+                    .map(|item| {
+                        let title = item["title"].as_str().unwrap_or_default();
+                        let words = title_keywords(title);
+                        let matched: Vec<&String> = keywords.iter().filter(|k| words.contains(k)).collect();
+                        // Share of the new title's keywords found in this title, to two decimals
+                        let similarity = (matched.len() as f64 / keywords.len() as f64 * 100.0).round() / 100.0;
+                        json!({
+                            "number": item["number"],
+                            "title": title,
+                            "state": item["state"],
+                            "url": item["html_url"],
+                            "matched_keywords": matched,
+                            "similarity": similarity,
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        // Stable sort keeps GitHub's relevance order among equally similar issues
+        matches.sort_by(|a, b| b["similarity"].as_f64().partial_cmp(&a["similarity"].as_f64()).unwrap_or(std::cmp::Ordering::Equal));
+        matches.truncate(param.limit.unwrap_or(5) as usize);
+        
+        let summary = json!({"keywords": keywords, "matches": matches});
+        Ok(command_success(summary.to_string(), &result))
+    }
+
+    /// Lock the conversation of an issue or pull request
+    #[tool(description = "Lock the conversation of an issue or pull request so only collaborators can comment, with an optional reason: off-topic, too heated, resolved or spam")]
+    async fn lock_issue(
+        &self,
+        #[tool(aggr)] param: LockIssueParam,
+    ) -> Result<CallToolResult, McpError> {
+        let mut args = vec![
+            "api".to_string(),
+            "--method".to_string(),
+            "PUT".to_string(),
+            format!("repos/{}/{}/issues/{}/lock", param.owner, param.repo, param.number),
+        ];
+        if let Some(reason) = param.lock_reason {
+            args.push("-f".to_string());
+            args.push(format!("lock_reason={}", reason.as_str()));
+        }
+        let result = self.run_gh_command(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if result.success {
+            Ok(command_success(format!("Locked #{}", param.number), &result))
+        } else {
+            Err(command_error("Failed to lock issue", &result))
+        }
+    }
+
+    /// Unlock the conversation of an issue or pull request
+    #[tool(description = "Unlock the conversation of an issue or pull request")]
+    async fn unlock_issue(
+        &self,
+        #[tool(aggr)] param: IssueParam,
+    ) -> Result<CallToolResult, McpError> {
+        let args = vec![
+            "api".to_string(),
+            "--method".to_string(),
+            "DELETE".to_string(),
+            format!("repos/{}/{}/issues/{}/lock", param.owner, param.repo, param.number),
+        ];
+        let result = self.run_gh_command(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if result.success {
+            Ok(command_success(format!("Unlocked #{}", param.number), &result))
+        } else {
+            Err(command_error("Failed to unlock issue", &result))
+        }
+    }
+
+    /// Pin an issue to its repository
+    #[tool(description = "Pin an issue to the top of its repository's issue list (at most 3 pinned issues per repository)")]
+    async fn pin_issue(
+        &self,
+        #[tool(aggr)] param: PinIssueParam,
+    ) -> Result<CallToolResult, McpError> {
+        let result = self.issue_mutation(&param, PIN_ISSUE_MUTATION, "Failed to pin issue").await?;
+        Ok(command_success(format!("Pinned #{}", param.number), &result))
+    }
+
+    /// Unpin an issue
+    #[tool(description = "Unpin a pinned issue from its repository")]
+    async fn unpin_issue(
+        &self,
+        #[tool(aggr)] param: PinIssueParam,
+    ) -> Result<CallToolResult, McpError> {
+        let result = self.issue_mutation(&param, UNPIN_ISSUE_MUTATION, "Failed to unpin issue").await?;
+        Ok(command_success(format!("Unpinned #{}", param.number), &result))
+    }
+
+    /// Move an issue to another repository
+    #[tool(description = "Transfer an issue to another repository, returning the URL of the issue in its new repository")]
+    async fn transfer_issue(
+        &self,
+        #[tool(aggr)] param: TransferIssueParam,
+    ) -> Result<CallToolResult, McpError> {
+        // The REST API has no transfer endpoint; gh issue transfer uses GraphQL
+        let args = vec![
+            "issue".to_string(),
+            "transfer".to_string(),
+            param.number.to_string(),
+            format!("{}/{}", param.target_owner, param.target_repo),
+            "--repo".to_string(),
+            format!("{}/{}", param.owner, param.repo),
+        ];
+        let result = self.run_gh_command(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if result.success {
+            Ok(command_success(result.output.trim().to_string(), &result))
+        } else {
+            Err(command_error("Failed to transfer issue", &result))
+        }
+    }
+
+    /// Apply the same label changes to many issues
+    #[tool(description = "Add and remove the same labels on many issues or pull requests at once, returning success or the error for each number")]
+    async fn bulk_label(
+        &self,
+        #[tool(aggr)] param: BulkLabelParam,
+    ) -> Result<CallToolResult, McpError> {
+        let remove_labels = param.remove_labels.unwrap_or_default();
+        if param.numbers.is_empty() {
+            return Err(McpError::invalid_params("numbers must not be empty", None));
+        }
+        if param.add_labels.is_empty() && remove_labels.is_empty() {
+            return Err(McpError::invalid_params("Set add_labels or remove_labels", None));
+        }
+        
+        // A repeated number would run twice and collapse into one result entry
+        let mut numbers = param.numbers;
+        numbers.sort_unstable();
+        numbers.dedup();
+        
+        let repo = format!("{}/{}", param.owner, param.repo);
+        let semaphore = Arc::new(Semaphore::new(FAN_OUT_CONCURRENCY));
+        let mut edits = JoinSet::new();
+        let mut task_numbers = HashMap::new();
+        for number in numbers {
+            let mut args = vec![
+                "issue".to_string(),
+                "edit".to_string(),
+                number.to_string(),
+                "--repo".to_string(),
+                repo.clone(),
+            ];
+            if !param.add_labels.is_empty() {
+                args.push("--add-label".to_string());
+                args.push(param.add_labels.join(","));
+            }
+            if !remove_labels.is_empty() {
+                args.push("--remove-label".to_string());
+                args.push(remove_labels.join(","));
+            }
+            let service = self.clone();
+            let semaphore = semaphore.clone();
+            let task = edits.spawn(
+                async move {
+                    let _permit = semaphore.acquire_owned().await;
+                    service.run_gh_command(args).await
+                }
+                .instrument(tracing::Span::current()),
+            );
+            task_numbers.insert(task.id(), number);
+        }
+        
+        let mut results = serde_json::Map::new();
+        let mut failed = 0;
+        while let Some(edit) = edits.join_next_with_id().await {
+            // A panicked task still has a number, so edits already applied are reported
+            let (number, outcome) = match edit {
+                Ok((id, result)) => {
+                    let outcome = if result.success {
+                        json!({"success": true})
+                    } else {
+                        failed += 1;
+                        let mut data = command_error_data(&result);
+                        data["success"] = json!(false);
+                        data
+                    };
+                    *self.last_result.lock().await = Some(result);
+                    (task_numbers[&id], outcome)
+                }
+                Err(e) => {
+                    failed += 1;
+                    (task_numbers[&e.id()], json!({"success": false, "error": format!("Label edit failed: {}", e)}))
+                }
+            };
+            results.insert(number.to_string(), outcome);
+        }
+        
+        let summary = json!({"succeeded": results.len() - failed, "failed": failed, "results": results});
+        Ok(CallToolResult::success(vec![Content::text(summary.to_string())]))
+    }
+
+    /// Assign an issue to the current user
+    #[tool(description = "Take an issue: assign it to the authenticated user and return its resulting assignees")]
+    async fn take_issue(
+        &self,
+        #[tool(aggr)] param: IssueParam,
+    ) -> Result<CallToolResult, McpError> {
+        let repo = format!("{}/{}", param.owner, param.repo);
+        let args = vec![
+            "issue".to_string(),
+            "edit".to_string(),
+            param.number.to_string(),
+            "--repo".to_string(),
+            repo.clone(),
+            "--add-assignee".to_string(),
+            "@me".to_string(),
+        ];
+        let result = self.run_gh_command(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if !result.success {
+            return Err(command_error("Failed to assign issue", &result));
+        }
+        
+        // Read the assignees back to confirm the assignment took effect
+        let args = vec![
+            "issue".to_string(),
+            "view".to_string(),
+            param.number.to_string(),
+            "--repo".to_string(),
+            repo,
+            "--json".to_string(),
+            "assignees".to_string(),
+            "--jq".to_string(),
+            "[.assignees[].login]".to_string(),
+        ];
+        let view = self.run_gh_read(args).await;
+        *last_result = Some(view.clone());
+        
+        if !view.success {
+            return Err(command_error("Failed to read issue assignees", &view));
+        }
+        let summary = json!({"number": param.number, "assignees": parse_json_output(&view.output)?});
+        Ok(command_success(summary.to_string(), &result))
+    }
+
+    /// List pull requests of specified repository
+    #[tool(description = "List pull requests of specified repository. Set fields to choose the returned JSON fields")]
+    async fn list_prs(
+        &self,
+        #[tool(aggr)] param: ListPRsParam,
+    ) -> Result<CallToolResult, McpError> {
+        let repo = format!("{}/{}", param.owner, param.repo);
+        let fields = json_fields(param.fields, "number,title,state,url", PR_JSON_FIELDS)?;
+        let args = vec!["pr".to_string(), "list".to_string(), "--repo".to_string(), repo, "--json".to_string(), fields];
+        let result = self.run_gh_read(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if result.success {
+            Ok(command_success(result.output.clone(), &result))
+        } else {
+            Err(command_error("Failed to get pull requests list", &result))
+        }
+    }
+
+    /// List discussions of a repository
+    #[tool(description = "List GitHub Discussions of a repository, newest first, with number, title, category, author, createdAt and url. Optionally only one category, by name or slug")]
+    async fn list_discussions(
+        &self,
+        #[tool(aggr)] param: ListDiscussionsParam,
+    ) -> Result<CallToolResult, McpError> {
+        let category_id = match &param.category {
+            Some(category) => Some(self.discussion_category(&param.owner, &param.repo, category).await?.1),
+            None => None,
+        };
+        let first = param.limit.unwrap_or(30).min(100);
+        let args = discussions_query_args(&param.owner, &param.repo, first, category_id.as_deref());
+        let result = self.run_gh_read(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if !result.success {
+            return Err(command_error("Failed to get discussions", &result));
+        }
+        let body = parse_json_output(&result.output)?;
+        let discussions: Vec<Value> = body["data"]["repository"]["discussions"]["nodes"]
+            .as_array()
+            .cloned()
+            .unwrap_or_default()
+            .iter()
+            .map(|discussion| {
+                json!({
+                    "number": discussion["number"],
+                    "title": discussion["title"],
+                    "category": discussion["category"]["name"],
+                    "author": discussion["author"]["login"],
+                    "createdAt": discussion["createdAt"],
+                    "url": discussion["url"],
+                })
+            })
+            .collect();
+        Ok(command_success(Value::from(discussions).to_string(), &result))
+    }
+
+    /// Get a discussion with its comments
+    #[tool(description = "Get a GitHub Discussion with number, title, body, category, author, upvoteCount, url and its first 50 top-level comments with their ids, marking the accepted answer")]
+    async fn get_discussion(
+        &self,
+        #[tool(aggr)] param: GetDiscussionParam,
+    ) -> Result<CallToolResult, McpError> {
+        let args = vec![
+            "api".to_string(),
+            "graphql".to_string(),
+            "-f".to_string(),
+            format!("query={}", DISCUSSION_QUERY),
+            "-f".to_string(),
+            format!("owner={}", param.owner),
+            "-f".to_string(),
+            format!("repo={}", param.repo),
+            "-F".to_string(),
+            format!("number={}", param.number),
+        ];
+        let result = self.run_gh_read(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if !result.success {
+            return Err(command_error("Failed to get discussion", &result));
+        }
+        let body = parse_json_output(&result.output)?;
+        let discussion = &body["data"]["repository"]["discussion"];
+        if discussion.is_null() {
+            return Err(McpError::invalid_params(
+                format!("Discussion {} not found in {}/{}", param.number, param.owner, param.repo),
+                None,
+            ));
+        }
+        let comments: Vec<Value> = discussion["comments"]["nodes"]
+            .as_array()
+            .cloned()
+            .unwrap_or_default()
+            .iter()
+            .map(|comment| {
+                json!({
+                    "id": comment["id"],
+                    "author": comment["author"]["login"],
+                    "body": comment["body"],
+                    "createdAt": comment["createdAt"],
+                    "upvoteCount": comment["upvoteCount"],
+                    "isAnswer": comment["id"] == discussion["answer"]["id"],
+                })
+            })
+            .collect();
+        let summary = json!({
+            "number": discussion["number"],
+            "title": discussion["title"],
+            "body": discussion["body"],
+            "category": discussion["category"]["name"],
+            "author": discussion["author"]["login"],
+            "createdAt": discussion["createdAt"],
+            "upvoteCount": discussion["upvoteCount"],
+            "commentCount": discussion["comments"]["totalCount"],
+            "comments": comments,
+            "url": discussion["url"],
+        });
+        Ok(command_success(summary.to_string(), &result))
+    }
+
+    /// Comment on a discussion
+    #[tool(description = "Comment on a GitHub Discussion, or reply in the thread of one of its top-level comments with reply_to_id, and return the new comment id and URL")]
+    async fn create_discussion_comment(
+        &self,
+        #[tool(aggr)] param: CreateDiscussionCommentParam,
+    ) -> Result<CallToolResult, McpError> {
+        let args = vec![
+            "api".to_string(),
+            "graphql".to_string(),
+            "-f".to_string(),
+            format!("query={}", DISCUSSION_ID_QUERY),
+            "-f".to_string(),
+            format!("owner={}", param.owner),
+            "-f".to_string(),
+            format!("repo={}", param.repo),
+            "-F".to_string(),
+            format!("number={}", param.discussion_number),
+            "--jq".to_string(),
+            ".data.repository.discussion.id".to_string(),
+        ];
+        let result = self.run_gh_read(args).await;
+        if !result.success {
+            let mut last_result = self.last_result.lock().await;
+            *last_result = Some(result.clone());
+            return Err(command_error("Failed to get discussion", &result));
+        }
+        
+        let request = json!({
+            "query": ADD_DISCUSSION_COMMENT_MUTATION,
+            "variables": {
+                "discussionId": result.output.trim(),
+                "body": param.body,
+                "replyToId": param.reply_to_id,
+            },
+        });
+        let args = vec![
+            "api".to_string(),
+            "graphql".to_string(),
+            "--input".to_string(),
+            "-".to_string(),
+        ];
+        let result = self.run_gh_command_with_input(args, request.to_string()).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if !result.success {
+            return Err(command_error("Failed to comment on discussion", &result));
+        }
+        let body = parse_json_output(&result.output)?;
+        let comment = &body["data"]["addDiscussionComment"]["comment"];
+        let created = json!({"id": comment["id"], "url": comment["url"]});
+        Ok(command_success(created.to_string(), &result))
+    }
+
+    /// Create a discussion in a repository
+    #[tool(description = "Open a new GitHub Discussion in a repository category and return its number and URL. category_id takes the category node ID, name or slug")]
+    async fn create_discussion(
+        &self,
+        #[tool(aggr)] param: CreateDiscussionParam,
+    ) -> Result<CallToolResult, McpError> {
+        let (repository_id, category_id) = self.discussion_category(&param.owner, &param.repo, &param.category_id).await?;
+        // The body goes over stdin so long discussions do not hit argument length limits
+        let request = json!({
+            "query": CREATE_DISCUSSION_MUTATION,
+            "variables": {
+                "repositoryId": repository_id,
+                "categoryId": category_id,
+                "title": param.title,
+                "body": param.body,
+            },
+        });
+        let args = vec![
+            "api".to_string(),
+            "graphql".to_string(),
+            "--input".to_string(),
+            "-".to_string(),
+        ];
+        let result = self.run_gh_command_with_input(args, request.to_string()).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if !result.success {
+            return Err(command_error("Failed to create discussion", &result));
+        }
+        let body = parse_json_output(&result.output)?;
+        let discussion = &body["data"]["createDiscussion"]["discussion"];
+        let created = json!({"number": discussion["number"], "url": discussion["url"]});
+        Ok(command_success(created.to_string(), &result))
+    }
+
+    /// List open pull requests that are ready to merge
+    #[tool(description = "List open pull requests of specified repository that are ready to merge: not drafts, no merge conflicts, approved (or no review required) and all checks passing. Inspects up to 100 open PRs")]
+    async fn list_mergeable_prs(
+        &self,
+        #[tool(aggr)] param: RepoParam,
+    ) -> Result<CallToolResult, McpError> {
+        let repo = format!("{}/{}", param.owner, param.repo);
+        let args = vec![
+            "pr".to_string(),
+            "list".to_string(),
+            "--repo".to_string(),
+            repo.clone(),
+            "--state".to_string(),
+            "open".to_string(),
+            "--limit".to_string(),
+            MERGEABLE_PR_SCAN_LIMIT.to_string(),
+            "--json".to_string(),
+            "number".to_string(),
+            "--jq".to_string(),
+            ".[].number".to_string(),
+        ];
+        let result = self.run_gh_read(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        drop(last_result);
+        
+        if !result.success {
+            return Err(command_error("Failed to get pull requests list", &result));
+        }
+        
+        // mergeable is only computed when a single PR is requested, so view each one
+        let semaphore = Arc::new(Semaphore::new(FAN_OUT_CONCURRENCY));
+        let mut views = JoinSet::new();
+        for number in result.output.split_whitespace().map(str::to_string) {
+            let args = vec![
+                "pr".to_string(),
+                "view".to_string(),
+                number.clone(),
+                "--repo".to_string(),
+                repo.clone(),
+                "--json".to_string(),
+                "number,title,url,isDraft,mergeable,reviewDecision,statusCheckRollup".to_string(),
+            ];
+            let service = self.clone();
+            let semaphore = semaphore.clone();
+            views.spawn(
+                async move {
+                    let _permit = semaphore.acquire_owned().await;
+                    (number, service.run_gh_read(args).await)
+                }
+                .instrument(tracing::Span::current()),
+            );
+        }
+        
+        let mut ready = Vec::new();
+        let mut failed = Vec::new();
+        while let Some(view) = views.join_next().await {
+            let (number, view) = view.map_err(|e| McpError::internal_error(format!("Pull request check failed: {}", e), None))?;
+            let pr = match serde_json::from_str::<Value>(&view.output) {
+                Ok(pr) if view.success => pr,
+                _ => {
+                    let mut data = command_error_data(&view);
+                    data["number"] = json!(number);
+                    failed.push(data);
+                    continue;
+                }
+            };
+            if pr_ready_to_merge(&pr) {
+                ready.push(json!({"number": pr["number"], "title": pr["title"], "url": pr["url"]}));
+            }
+        }
+        ready.sort_by_key(|pr| pr["number"].as_u64());
+        
+        let summary = json!({"ready": ready, "failed": failed});
+        Ok(command_success(summary.to_string(), &result))
+    }
+
+    /// Create pull request
+    #[tool(description = "Create pull request. For PRs from a fork, set head_repo to the fork owner so head becomes owner:branch")]
+    async fn create_pr(
+        &self,
+        #[tool(aggr)] param: CreatePRParam,
+    ) -> Result<CallToolResult, McpError> {
+        let mut args = vec!["pr".to_string(), "create".to_string()];
+        
+        if let Some(repo) = param.repo {
+            args.push("--repo".to_string());
+            args.push(repo);
+        }
+        
+        args.push("--title".to_string());
+        args.push(param.title);
+        
+        if let Some(body) = param.body {
+            args.push("--body".to_string());
+            args.push(body);
+        }
+        
+        args.push("--base".to_string());
+        args.push(param.base);
+        
+        args.push("--head".to_string());
+        args.push(resolve_pr_head(&param.head, param.head_repo.as_deref())?);
+        
+        let result = self.run_gh_command(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if result.success {
+            Ok(command_success(result.output.clone(), &result))
+        } else {
+            Err(command_error("Failed to create pull request", &result))
+        }
+    }
+
+    /// Clone repository
+    #[tool(description = "Clone GitHub repository")]
+    async fn clone_repo(
+        &self,
+        #[tool(aggr)] param: CloneRepoParam,
+    ) -> Result<CallToolResult, McpError> {
+        let mut args = vec!["repo".to_string(), "clone".to_string(), param.repo];
+        
+        if let Some(dir) = param.directory {
+            args.push(dir);
+        }
+        
+        let result = self.run_gh_command_with_timeout(args, CommandTimeout::Long).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if result.success {
+            Ok(command_success(result.output.clone(), &result))
+        } else {
+            Err(command_error("Failed to clone repository", &result))
+        }
+    }
+
+    /// Run any GitHub CLI command
+    #[tool(description = "Run any GitHub CLI command")]
+    async fn run_command(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "GitHub CLI command without gh prefix")]
+        command: String,
+    ) -> Result<CallToolResult, McpError> {
+        let args: Vec<String> = command.split_whitespace().map(|s| s.to_string()).collect();
+        let result = self.run_gh_command(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if result.success {
+            Ok(command_success(result.output.clone(), &result))
+        } else {
+            Err(command_error("Failed to execute command", &result))
+        }
+    }
+
+    /// Call any GitHub REST or GraphQL API endpoint
+    #[tool(description = "Call any GitHub API endpoint through gh api, for endpoints without a dedicated tool. Takes an API path, method, query parameters, typed fields or a raw body, and headers. Returns the HTTP status, rate limit and pagination headers, and the body (truncated beyond 100 KB). Non-2xx responses are returned as error results with the same shape. Set paginate to follow all pages of a GET list endpoint and get {count, truncated, items} with items merged across pages, bounded by max_items")]
+    async fn gh_api(
+        &self,
+        #[tool(aggr)] param: GhApiParam,
+    ) -> Result<CallToolResult, McpError> {
+        let mut args = gh_api_args(&param)?;
+        if param.paginate {
+            return self.gh_api_paginated(&param, args).await;
+        }
+        args.push("-i".to_string());
+        let result = match (&param.raw_body, param.method.unwrap_or(ApiMethod::Get)) {
+            (Some(body), _) => self.run_gh_command_with_input(args, body.clone()).await,
+            (None, ApiMethod::Get) => self.run_gh_read(args).await,
+            (None, _) => self.run_gh_command(args).await,
+        };
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        let response = parse_api_response(&result.output);
+        // Without a status line gh failed before getting a response, e.g. on auth or network errors
+        if response.status == 0 {
+            if result.success {
+                return Ok(command_success(result.output.clone(), &result));
+            }
+            return Err(command_error("Failed to call API", &result));
+        }
+        let headers: serde_json::Map<String, Value> = API_RESPONSE_HEADERS
+            .iter()
+            .filter_map(|name| response.headers.get(*name).map(|value| (name.to_string(), json!(value))))
+            .collect();
+        let truncated = response.body.chars().count() > GH_API_MAX_BODY;
+        let body = match serde_json::from_str::<Value>(&response.body) {
+            Ok(body) if !truncated => body,
+            _ => json!(truncate_fragment(&response.body, GH_API_MAX_BODY)),
+        };
+        let mut summary = json!({"status": response.status, "headers": headers, "body": body});
+        if truncated {
+            summary["notice"] = json!(format!(
+                "Body truncated to {} of {} characters; narrow the request with query parameters such as per_page",
+                GH_API_MAX_BODY,
+                response.body.chars().count()
+            ));
+        }
+        if (200..300).contains(&response.status) {
+            Ok(CallToolResult::success(vec![Content::text(summary.to_string())]))
+        } else {
+            Ok(CallToolResult::error(vec![Content::text(summary.to_string())]))
+        }
+    }
+
+    /// Run a GraphQL query or mutation
+    #[tool(description = "Run a GitHub GraphQL query or mutation with variables and return data and errors separately. GraphQL reports failures as an errors array, so a result with errors and no data is an error result; partial data with errors is returned as success with both")]
+    async fn graphql(
+        &self,
+        #[tool(aggr)] param: GraphqlParam,
+    ) -> Result<CallToolResult, McpError> {
+        // The whole request goes over stdin, so long documents avoid argv limits and variables keep their JSON types
+        let request = json!({"query": param.query, "variables": param.variables});
+        let args = vec![
+            "api".to_string(),
+            "graphql".to_string(),
+            "--input".to_string(),
+            "-".to_string(),
+        ];
+        let result = self.run_gh_command_with_input(args, request.to_string()).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        // gh exits non-zero on GraphQL errors but still prints the response body
+        let body = match serde_json::from_str::<Value>(&result.output) {
+            Ok(body) if body.is_object() => body,
+            _ if result.success => parse_json_output(&result.output)?,
+            _ => return Err(command_error("Failed to run GraphQL query", &result)),
+        };
+        let errors = body["errors"].as_array().cloned().unwrap_or_default();
+        let summary = json!({"data": body["data"], "errors": errors});
+        if !errors.is_empty() && body["data"].is_null() {
+            Ok(CallToolResult::error(vec![Content::text(summary.to_string())]))
+        } else {
+            Ok(CallToolResult::success(vec![Content::text(summary.to_string())]))
+        }
+    }
+
+    /// Search repositories across GitHub
+    #[tool(description = "Search GitHub repositories by name, description, owner, language, topic or stars")]
+    async fn search_repos(
+        &self,
+        #[tool(aggr)] param: SearchReposParam,
+    ) -> Result<CallToolResult, McpError> {
+        let (summary, result) = self.repo_search(&param).await?;
+        Ok(command_success(summary.to_string(), &result))
+    }
+
+    /// Search code across GitHub
+    #[tool(description = "Search code on GitHub. Requires authentication and has a low rate limit, so prefer narrow queries scoped by repo or owner")]
+    async fn search_code(
+        &self,
+        #[tool(aggr)] param: SearchCodeParam,
+    ) -> Result<CallToolResult, McpError> {
+        let (summary, result) = self.code_search(&param).await?;
+        Ok(command_success(summary.to_string(), &result))
+    }
+
+    /// Search issues across GitHub
+    #[tool(description = "Search GitHub issues by text, repository, author, state or label")]
+    async fn search_issues(
+        &self,
+        #[tool(aggr)] param: SearchIssuesParam,
+    ) -> Result<CallToolResult, McpError> {
+        let (summary, result) = self.issue_search(&param, false).await?;
+        Ok(command_success(summary.to_string(), &result))
+    }
+
+    /// Search pull requests across GitHub
+    #[tool(description = "Search GitHub pull requests by text, repository, author, state or label")]
+    async fn search_prs(
+        &self,
+        #[tool(aggr)] param: SearchIssuesParam,
+    ) -> Result<CallToolResult, McpError> {
+        let (summary, result) = self.issue_search(&param, true).await?;
+        Ok(command_success(summary.to_string(), &result))
+    }
+
+    /// Search commits across GitHub
+    #[tool(description = "Search commits on GitHub by message, author, committer, date range or hash. This is the slowest search endpoint; check incomplete_results, which is true when GitHub timed out and returned partial results")]
+    async fn search_commits(
+        &self,
+        #[tool(aggr)] param: SearchCommitsParam,
+    ) -> Result<CallToolResult, McpError> {
+        let (summary, result) = self.commit_search(&param).await?;
+        Ok(command_success(summary.to_string(), &result))
+    }
+
+    /// Search users and organizations
+    #[tool(description = "Search GitHub users and organizations by name, location, language or follower count")]
+    async fn search_users(
+        &self,
+        #[tool(aggr)] param: SearchUsersParam,
+    ) -> Result<CallToolResult, McpError> {
+        let (summary, result) = self.user_search(&param).await?;
+        Ok(command_success(summary.to_string(), &result))
+    }
+
+    /// Search any kind of GitHub entity
+    #[tool(description = "Search GitHub repos, code, issues, prs, commits or users through one tool. Filters are validated per kind; results use a common {kind, items, total, incomplete} envelope")]
+    async fn search(
+        &self,
+        #[tool(aggr)] param: SearchParam,
+    ) -> Result<CallToolResult, McpError> {
+        let kind = param.kind;
+        validate_search_filters(kind, &param.filters)?;
+        let filters = param.filters;
+        let query = param.query;
+        let limit = param.limit;
+
+        let (summary, result) = match kind {
+            SearchKind::Repos => {
+                let param = SearchReposParam {
+                    query,
+                    owner: filters.owner,
+                    language: filters.language,
+                    topic: filters.topic,
+                    stars: filters.stars,
+                    limit,
+                };
+                self.repo_search(&param).await?
+            }
+            SearchKind::Code => {
+                let param = SearchCodeParam {
+                    query,
+                    repo: filters.repo,
+                    owner: filters.owner,
+                    language: filters.language,
+                    filename: filters.filename,
+                    extension: filters.extension,
+                    path: filters.path,
+                    match_in: filters.match_in,
+                    limit,
+                };
+                self.code_search(&param).await?
+            }
+            SearchKind::Issues | SearchKind::Prs => {
+                let param = SearchIssuesParam {
+                    query,
+                    repo: filters.repo,
+                    owner: filters.owner,
+                    author: filters.author,
+                    state: filters.state,
+                    label: filters.label,
+                    limit,
+                };
+                self.issue_search(&param, kind == SearchKind::Prs).await?
+            }
+            SearchKind::Commits => {
+                let param = SearchCommitsParam {
+                    query,
+                    author: filters.author,
+                    committer: filters.committer,
+                    repo: filters.repo,
+                    owner: filters.owner,
+                    author_date_from: filters.author_date_from,
+                    author_date_to: filters.author_date_to,
+                    merge: filters.merge,
+                    hash: filters.hash,
+                    limit,
+                    sort: None,
+                };
+                self.commit_search(&param).await?
+            }
+            SearchKind::Users => {
+                let param = SearchUsersParam {
+                    query,
+                    account_type: filters.account_type,
+                    location: filters.location,
+                    language: filters.language,
+                    followers: filters.followers,
+                    limit,
+                };
+                self.user_search(&param).await?
+            }
+        };
+
+        let envelope = json!({
+            "kind": kind,
+            "items": summary["items"],
+            "total": summary["total_count"],
+            "incomplete": summary["incomplete_results"].as_bool().unwrap_or(false),
+        });
+        Ok(command_success(envelope.to_string(), &result))
+    }
+
+    /// View a user profile
+    #[tool(description = "Get profile of a GitHub user or organization by login")]
+    async fn user_view(
+        &self,
+        #[tool(aggr)] param: UserParam,
+    ) -> Result<CallToolResult, McpError> {
+        let args = vec!["api".to_string(), format!("users/{}", param.login)];
+        let result = self.run_gh_read(args).await;
+
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+
+        if !result.success {
+            return Err(command_error("Failed to get user profile", &result));
+        }
+
+        let user = parse_json_output(&result.output)?;
+        let profile = json!({
+            "login": user["login"],
+            "name": user["name"],
+            "type": user["type"],
+            "company": user["company"],
+            "bio": user["bio"],
+            "public_repos": user["public_repos"],
+            "created_at": user["created_at"],
+            "url": user["html_url"],
+        });
+        Ok(command_success(profile.to_string(), &result))
+    }
+
+    /// Log in to GitHub
+    #[tool(description = "Log in to GitHub CLI or switch account. Provide with_token for headless environments where interactive login is not possible")]
+    async fn auth_login(
+        &self,
+        #[tool(aggr)] param: AuthLoginParam,
+    ) -> Result<CallToolResult, McpError> {
+        self.forget_current_user().await;
+        let mut args = vec!["auth".to_string(), "login".to_string()];
+        
+        if let Some(host) = &param.github_host {
+            args.push("--hostname".to_string());
+            args.push(host.clone());
+        }
+        
+        if let Some(scopes) = param.scopes.filter(|scopes| !scopes.is_empty()) {
+            args.push("--scopes".to_string());
+            args.push(scopes.join(","));
+        }
+        
+        let result = match param.with_token {
+            Some(token) => {
+                let token = require_token(&token)?;
+                args.push("--with-token".to_string());
+                self.run_gh_command_with_input(args, token.to_string()).await
+            }
+            None => self.run_gh_command(args).await,
+        };
+        self.forget_current_user().await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if !result.success {
+            return Err(command_error("Failed to log in", &result));
+        }
+        
+        let mut status_args = vec!["auth".to_string(), "status".to_string()];
+        if let Some(host) = param.github_host {
+            status_args.push("--hostname".to_string());
+            status_args.push(host);
+        }
+        let status = self.run_gh_read(status_args).await;
+        *last_result = Some(status.clone());
+        
+        Ok(command_success(status.output.clone(), &status))
+    }
+
+    /// Log in to GitHub with a token
+    #[tool(description = "Log in to GitHub CLI with a personal access token for headless setups. Returns the authenticated user")]
+    async fn login_with_token(
+        &self,
+        #[tool(aggr)] param: LoginWithTokenParam,
+    ) -> Result<CallToolResult, McpError> {
+        self.forget_current_user().await;
+        let token = require_token(&param.token)?;
+        let args = vec!["auth".to_string(), "login".to_string(), "--with-token".to_string()];
+        let result = self.run_gh_command_with_input(args, token.to_string()).await;
+        self.forget_current_user().await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if !result.success {
+            return Err(command_error("Failed to log in with token", &result));
+        }
+        
+        let status = self.run_gh_read(vec!["auth".to_string(), "status".to_string()]).await;
+        *last_result = Some(status.clone());
+        
+        // gh prints the status report to stderr on older versions
+        let report = format!("{}{}", status.output, status.stderr.clone().unwrap_or_default());
+        let summary = json!({"user": active_user_from_status(&report)});
+        Ok(command_success(summary.to_string(), &status))
+    }
+
+    /// Get the authenticated user's profile
+    #[tool(description = "Get the authenticated user's login, name, public email and account type. Cached until the account changes")]
+    async fn whoami(&self) -> Result<CallToolResult, McpError> {
+        let mut current_user = self.current_user.lock().await;
+        if let Some(user) = current_user.as_ref() {
+            return Ok(CallToolResult::success(vec![Content::text(user.to_string())]));
+        }
+        
+        let args = vec!["api".to_string(), "user".to_string()];
+        let result = self.run_gh_read(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if !result.success {
+            return Err(command_error("Failed to get current user", &result));
+        }
+        
+        let user = parse_json_output(&result.output)?;
+        let profile = json!({
+            "login": user["login"],
+            "name": user["name"],
+            "email": user["email"],
+            "type": user["type"],
+        });
+        *current_user = Some(profile.clone());
+        Ok(command_success(profile.to_string(), &result))
+    }
+
+    /// Report the environment gh runs in
+    #[tool(description = "Report which gh executable and config directory the server uses, the active host, and which token environment variables are set (never their values). Use it to debug deployment issues; use auth_status for login state")]
+    async fn gh_environment(&self) -> Result<CallToolResult, McpError> {
+        let status = self.run_gh_read(vec!["auth".to_string(), "status".to_string()]).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(status.clone());
+        
+        // GH_HOST overrides the host of the active account
+        let report = format!("{}{}", status.output, status.stderr.clone().unwrap_or_default());
+        let active_host = std::env::var("GH_HOST")
+            .ok()
+            .filter(|host| !host.is_empty())
+            .or_else(|| active_host_from_status(&report));
+        let token_env: HashMap<&str, bool> = TOKEN_ENV_VARS
+            .iter()
+            .map(|name| (*name, std::env::var_os(name).is_some_and(|value| !value.is_empty())))
+            .collect();
+        let summary = json!({
+            "executable": find_in_path(&self.gh_binary).map(|path| path.display().to_string()),
+            "config_dir": gh_config_dir().map(|dir| dir.display().to_string()),
+            "config_dir_from_env": std::env::var_os("GH_CONFIG_DIR").is_some(),
+            "active_host": active_host,
+            "token_env": token_env,
+        });
+        Ok(CallToolResult::success(vec![Content::text(summary.to_string())]))
+    }
+
+    /// Get the current authentication token
+    #[tool(description = "Get the authentication token gh is using, for direct API calls. Handle the returned value as a secret")]
+    async fn auth_token(&self) -> Result<CallToolResult, McpError> {
+        let args = vec!["auth".to_string(), "token".to_string()];
+        let result = self.run_gh_read(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(redact_output(&result));
+        
+        if result.success {
+            // rmcp logs the whole response, so the token must be scrubbed even without a known prefix
+            register_secret(&result.output);
+            Ok(command_success(result.output.trim().to_string(), &result))
+        } else {
+            Err(command_error("Failed to get authentication token", &result))
+        }
+    }
+
+    /// List labels of a repository
+    #[tool(description = "List labels of specified repository")]
+    async fn list_labels(
+        &self,
+        #[tool(aggr)] param: ListLabelsParam,
+    ) -> Result<CallToolResult, McpError> {
+        let repo = format!("{}/{}", param.owner, param.repo);
+        let args = vec![
+            "label".to_string(),
+            "list".to_string(),
+            "--repo".to_string(),
+            repo,
+            "--json".to_string(),
+            "name,color,description".to_string(),
+            "--limit".to_string(),
+            param.limit.unwrap_or(100).to_string(),
+        ];
+        let result = self.run_cached(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if result.success {
+            Ok(command_success(result.output.clone(), &result))
+        } else {
+            Err(command_error("Failed to get labels list", &result))
+        }
+    }
+
+    /// Create a label
+    #[tool(description = "Create a label in specified repository. Set update_if_exists to update an existing label with the same name instead of failing")]
+    async fn create_label(
+        &self,
+        #[tool(aggr)] param: CreateLabelParam,
+    ) -> Result<CallToolResult, McpError> {
+        let color = normalize_label_color(&param.color)?;
+        let repo = format!("{}/{}", param.owner, param.repo);
+        let mut args = vec![
+            "label".to_string(),
+            "create".to_string(),
+            param.name.clone(),
+            "--repo".to_string(),
+            repo.clone(),
+            "--color".to_string(),
+            color.clone(),
+        ];
+        
+        if let Some(description) = &param.description {
+            args.push("--description".to_string());
+            args.push(description.clone());
+        }
+        
+        let mut result = self.run_gh_command(args).await;
+        let mut action = "created";
+        
+        let already_exists = result.error.as_deref().is_some_and(|e| e.contains("already exists"));
+        if !result.success && already_exists && param.update_if_exists {
+            let args = label_edit_args(repo, param.name.clone(), None, Some(color), param.description);
+            result = self.run_gh_command(args).await;
+            action = "updated";
+        }
+        
+        self.invalidate_cache();
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if result.success {
+            let summary = json!({"name": param.name, "action": action});
+            Ok(command_success(summary.to_string(), &result))
+        } else {
+            Err(command_error("Failed to create label", &result))
+        }
+    }
+
+    /// Edit a label
+    #[tool(description = "Rename a label or change its color or description")]
+    async fn edit_label(
+        &self,
+        #[tool(aggr)] param: EditLabelParam,
+    ) -> Result<CallToolResult, McpError> {
+        if param.new_name.is_none() && param.color.is_none() && param.description.is_none() {
+            return Err(McpError::invalid_params(
+                "Provide at least one of new_name, color or description",
+                None,
+            ));
+        }
+        let color = param.color.as_deref().map(normalize_label_color).transpose()?;
+        let repo = format!("{}/{}", param.owner, param.repo);
+        let args = label_edit_args(repo, param.name, param.new_name, color, param.description);
+        let result = self.run_gh_command(args).await;
+        
+        self.invalidate_cache();
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if result.success {
+            Ok(command_success(result.output.clone(), &result))
+        } else {
+            Err(command_error("Failed to edit label", &result))
+        }
+    }
+
+    /// Delete a label
+    #[tool(description = "Delete a label from specified repository. Requires confirm set to true")]
+    async fn delete_label(
+        &self,
+        #[tool(aggr)] param: DeleteLabelParam,
+    ) -> Result<CallToolResult, McpError> {
+        require_confirm(param.confirm, "delete the label")?;
+        let repo = format!("{}/{}", param.owner, param.repo);
+        let args = vec![
+            "label".to_string(),
+            "delete".to_string(),
+            param.name.clone(),
+            "--repo".to_string(),
+            repo,
+            "--yes".to_string(),
+        ];
+        let result = self.run_gh_command(args).await;
+        
+        self.invalidate_cache();
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if result.success {
+            Ok(command_success(format!("Deleted label {}", param.name), &result))
+        } else {
+            Err(command_error("Failed to delete label", &result))
+        }
+    }
+
+    /// Copy labels from one repository to another
+    #[tool(description = "Copy labels from a source repository to a target repository. Existing labels are kept unless overwrite is set; dry_run reports which labels would be created, updated or skipped")]
+    async fn clone_labels(
+        &self,
+        #[tool(aggr)] param: CloneLabelsParam,
+    ) -> Result<CallToolResult, McpError> {
+        let source = format!("{}/{}", param.source_owner, param.source_repo);
+        let target = format!("{}/{}", param.target_owner, param.target_repo);
+        let plan = plan_label_clone(
+            &self.fetch_labels(&source).await?,
+            &self.fetch_labels(&target).await?,
+            param.overwrite,
+        );
+        
+        if param.dry_run {
+            let summary = json!({"source": source, "target": target, "dry_run": true, "labels": plan});
+            return Ok(CallToolResult::success(vec![Content::text(summary.to_string())]));
+        }
+        
+        let mut args = vec!["label".to_string(), "clone".to_string(), source.clone(), "--repo".to_string(), target.clone()];
+        if param.overwrite {
+            args.push("--force".to_string());
+        }
+        let result = self.run_gh_command(args).await;
+        
+        self.invalidate_cache();
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if result.success {
+            let summary = json!({"source": source, "target": target, "dry_run": false, "labels": plan});
+            Ok(command_success(summary.to_string(), &result))
+        } else {
+            Err(command_error("Failed to clone labels", &result))
+        }
+    }
+
+    /// List milestones of a repository
+    #[tool(description = "List milestones of specified repository with open/closed issue counts and completion percentage")]
+    async fn list_milestones(
+        &self,
+        #[tool(aggr)] param: ListMilestonesParam,
+    ) -> Result<CallToolResult, McpError> {
+        let state = param.state.unwrap_or(ListState::Open);
+        let (summary, result) = self
+            .api_page(
+                &format!("repos/{}/{}/milestones", param.owner, param.repo),
+                vec![("state", state.as_str().to_string())],
+                &param.page,
+                shape_milestone,
+                "Failed to get milestones list",
+            )
+            .await?;
+        Ok(command_success(summary.to_string(), &result))
+    }
+
+    /// Create a milestone
+    #[tool(description = "Create a milestone in specified repository")]
+    async fn create_milestone(
+        &self,
+        #[tool(aggr)] param: CreateMilestoneParam,
+    ) -> Result<CallToolResult, McpError> {
+        let mut args = vec![
+            "api".to_string(),
+            "--method".to_string(),
+            "POST".to_string(),
+            format!("repos/{}/{}/milestones", param.owner, param.repo),
+        ];
+        args.extend(milestone_fields(Some(param.title), param.description, param.due_on, param.state)?);
+        let result = self.run_gh_command(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if !result.success {
+            return Err(command_error("Failed to create milestone", &result));
+        }
+        
+        let milestone = shape_milestone(&parse_json_output(&result.output)?);
+        Ok(command_success(milestone.to_string(), &result))
+    }
+
+    /// Edit a milestone
+    #[tool(description = "Change title, description, due date or state of a milestone")]
+    async fn edit_milestone(
+        &self,
+        #[tool(aggr)] param: EditMilestoneParam,
+    ) -> Result<CallToolResult, McpError> {
+        let fields = milestone_fields(param.title, param.description, param.due_on, param.state)?;
+        if fields.is_empty() {
+            return Err(McpError::invalid_params(
+                "Provide at least one of title, description, due_on or state",
+                None,
+            ));
+        }
+        let mut args = vec![
+            "api".to_string(),
+            "--method".to_string(),
+            "PATCH".to_string(),
+            format!("repos/{}/{}/milestones/{}", param.owner, param.repo, param.number),
+        ];
+        args.extend(fields);
+        let result = self.run_gh_command(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if !result.success {
+            return Err(command_error("Failed to edit milestone", &result));
+        }
+        
+        let milestone = shape_milestone(&parse_json_output(&result.output)?);
+        Ok(command_success(milestone.to_string(), &result))
+    }
+
+    /// Close a milestone
+    #[tool(description = "Close a milestone")]
+    async fn close_milestone(
+        &self,
+        #[tool(aggr)] param: MilestoneParam,
+    ) -> Result<CallToolResult, McpError> {
+        let args = vec![
+            "api".to_string(),
+            "--method".to_string(),
+            "PATCH".to_string(),
+            format!("repos/{}/{}/milestones/{}", param.owner, param.repo, param.number),
+            "-f".to_string(),
+            "state=closed".to_string(),
+        ];
+        let result = self.run_gh_command(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if !result.success {
+            return Err(command_error("Failed to close milestone", &result));
+        }
+        
+        let milestone = shape_milestone(&parse_json_output(&result.output)?);
+        Ok(command_success(milestone.to_string(), &result))
+    }
+
+    /// List repository secrets
+    #[tool(description = "List secret names and update times of specified repository. Values are never returned. The app selects the store: actions (default) secrets are used by workflow runs, dependabot secrets only by Dependabot-triggered runs and private registry access, codespaces secrets only in codespaces")]
+    async fn list_repo_secrets(
+        &self,
+        #[tool(aggr)] param: ListRepoSecretsParam,
+    ) -> Result<CallToolResult, McpError> {
+        let repo = format!("{}/{}", param.owner, param.repo);
+        let mut args = vec![
+            "secret".to_string(),
+            "list".to_string(),
+            "--repo".to_string(),
+            repo,
+            "--json".to_string(),
+            "name,updatedAt".to_string(),
+        ];
+        args.extend(secret_app_args(param.app));
+        let result = self.run_gh_read(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if result.success {
+            Ok(command_success(result.output.clone(), &result))
+        } else {
+            Err(command_error("Failed to get secrets list", &result))
+        }
+    }
+
+    /// Set a repository secret
+    #[tool(description = "Create or update a repository secret. The value is passed to gh on stdin and never recorded. The app selects the store: actions (default) secrets are used by workflow runs, dependabot secrets only by Dependabot-triggered runs and private registry access, codespaces secrets only in codespaces")]
+    async fn set_repo_secret(
+        &self,
+        #[tool(aggr)] param: SetRepoSecretParam,
+    ) -> Result<CallToolResult, McpError> {
+        validate_secret_name(&param.name)?;
+        let repo = format!("{}/{}", param.owner, param.repo);
+        let mut args = vec![
+            "secret".to_string(),
+            "set".to_string(),
+            param.name.clone(),
+            "--repo".to_string(),
+            repo,
+        ];
+        args.extend(secret_app_args(param.app));
+        let result = redact_secret(
+            &self.run_gh_command_with_input(args, param.value.clone()).await,
+            &param.value,
+        );
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if result.success {
+            Ok(command_success(format!("Set secret {}", param.name), &result))
+        } else {
+            Err(command_error("Failed to set secret", &result))
+        }
+    }
+
+    /// Delete a repository secret
+    #[tool(description = "Delete a repository secret from the actions (default), dependabot or codespaces store. Pass the same app the secret was set with. Requires confirm set to true")]
+    async fn delete_repo_secret(
+        &self,
+        #[tool(aggr)] param: DeleteRepoSecretParam,
+    ) -> Result<CallToolResult, McpError> {
+        require_confirm(param.confirm, "delete the secret")?;
+        let repo = format!("{}/{}", param.owner, param.repo);
+        let args = delete_secret_args(&param.name, &repo, param.app);
+        let result = self.run_gh_command(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if result.success {
+            Ok(command_success(format!("Deleted secret {}", param.name), &result))
+        } else {
+            Err(command_error("Failed to delete secret", &result))
+        }
+    }
+
+    /// List members of an organization with their role
+    #[tool(description = "List members of an organization with their role (admin or member), optionally only one role. Follows all pages unless limit is set. Requires membership of the organization")]
+    async fn list_org_members(
+        &self,
+        #[tool(aggr)] param: ListOrgMembersParam,
+    ) -> Result<CallToolResult, McpError> {
+        let path = format!("orgs/{}/members", param.org);
+        let role = param.role.unwrap_or(OrgRole::All);
+        let query = match role {
+            OrgRole::All => Vec::new(),
+            OrgRole::Admin => vec![("role", "admin".to_string())],
+            OrgRole::Member => vec![("role", "member".to_string())],
+        };
+        let result = self.api_list_all(&path, query).await;
+        if !result.success {
+            return Err(org_command_error("Failed to get organization members", &param.org, &result));
+        }
+        
+        // Listing admins once gives every member's role without a memberships call per user
+        let admins = match role {
+            OrgRole::All => self.logins_with_role(&path, "admin", &param.org).await?,
+            _ => Vec::new(),
+        };
+        let mut members: Vec<Value> = parse_json_lines(&result.output)?
+            .iter()
+            .map(|member| {
+                let login = member["login"].as_str().unwrap_or_default();
+                let role = match role {
+                    OrgRole::Admin => "admin",
+                    OrgRole::Member => "member",
+                    OrgRole::All if admins.iter().any(|admin| admin == login) => "admin",
+                    OrgRole::All => "member",
+                };
+                json!({"login": login, "role": role, "url": member["html_url"]})
+            })
+            .collect();
+        if let Some(limit) = param.limit {
+            members.truncate(limit as usize);
+        }
+        Ok(command_success(Value::from(members).to_string(), &result))
+    }
+
+    /// Invite a user to an organization or change their role
+    #[tool(description = "Add a user to an organization or change their role to member or admin (owner). Users who are not yet members get an invitation and the returned state is pending until they accept. Requires an organization owner token")]
+    async fn set_org_membership(
+        &self,
+        #[tool(aggr)] param: SetOrgMembershipParam,
+    ) -> Result<CallToolResult, McpError> {
+        let args = vec![
+            "api".to_string(),
+            "--method".to_string(),
+            "PUT".to_string(),
+            format!("orgs/{}/memberships/{}", param.org, param.username),
+            "-f".to_string(),
+            format!("role={}", param.role.as_str()),
+        ];
+        let result = self.run_gh_command(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if is_forbidden(&result) {
+            return Err(McpError::invalid_request(
+                format!("Failed to set organization membership: only owners of {} can manage members", param.org),
+                Some(command_error_data(&result)),
+            ));
+        }
+        if !result.success {
+            return Err(command_error("Failed to set organization membership", &result));
+        }
+        let membership = parse_json_output(&result.output)?;
+        let summary = json!({
+            "org": param.org,
+            "username": param.username,
+            "role": membership["role"],
+            "state": membership["state"],
+        });
+        Ok(command_success(summary.to_string(), &result))
+    }
+
+    /// List teams of an organization
+    #[tool(description = "List teams of an organization with id, slug, name, description, privacy, default repository permission, parent team and web URL. Follows all pages unless limit is set. Requires membership of the organization")]
+    async fn list_teams(
+        &self,
+        #[tool(aggr)] param: ListTeamsParam,
+    ) -> Result<CallToolResult, McpError> {
+        let result = self.api_list_all(&format!("orgs/{}/teams", param.org), Vec::new()).await;
+        if !result.success {
+            return Err(org_command_error("Failed to get teams", &param.org, &result));
+        }
+        let mut teams: Vec<Value> = parse_json_lines(&result.output)?
+            .iter()
+            .map(|team| {
+                json!({
+                    "id": team["id"],
+                    "slug": team["slug"],
+                    "name": team["name"],
+                    "description": team["description"],
+                    "privacy": team["privacy"],
+                    "permission": team["permission"],
+                    "parent": team["parent"]["slug"],
+                    "url": team["html_url"],
+                })
+            })
+            .collect();
+        if let Some(limit) = param.limit {
+            teams.truncate(limit as usize);
+        }
+        Ok(command_success(Value::from(teams).to_string(), &result))
+    }
+
+    /// List members of a team with their role
+    #[tool(description = "List members of an organization team with their team role (maintainer or member), optionally only one role. Follows all pages unless limit is set. Requires membership of the organization")]
+    async fn team_members(
+        &self,
+        #[tool(aggr)] param: TeamMembersParam,
+    ) -> Result<CallToolResult, McpError> {
+        let path = format!("orgs/{}/teams/{}/members", param.org, param.team_slug);
+        let role = param.role.unwrap_or(TeamRole::All);
+        let query = match role {
+            TeamRole::All => Vec::new(),
+            TeamRole::Maintainer => vec![("role", "maintainer".to_string())],
+            TeamRole::Member => vec![("role", "member".to_string())],
+        };
+        let result = self.api_list_all(&path, query).await;
+        if !result.success {
+            return Err(org_command_error("Failed to get team members", &param.org, &result));
+        }
+        
+        let maintainers = match role {
+            TeamRole::All => self.logins_with_role(&path, "maintainer", &param.org).await?,
+            _ => Vec::new(),
+        };
+        let mut members: Vec<Value> = parse_json_lines(&result.output)?
+            .iter()
+            .map(|member| {
+                let login = member["login"].as_str().unwrap_or_default();
+                let role = match role {
+                    TeamRole::Maintainer => "maintainer",
+                    TeamRole::Member => "member",
+                    TeamRole::All if maintainers.iter().any(|m| m == login) => "maintainer",
+                    TeamRole::All => "member",
+                };
+                json!({"login": login, "role": role, "url": member["html_url"]})
+            })
+            .collect();
+        if let Some(limit) = param.limit {
+            members.truncate(limit as usize);
+        }
+        Ok(command_success(Value::from(members).to_string(), &result))
+    }
+
+    /// List repositories a team can access
+    #[tool(description = "List repositories an organization team can access with name, full_name, the team's permission (admin, maintain, push, triage, pull or a custom role) and URL. Follows all pages unless limit is set. Requires membership of the organization")]
+    async fn list_team_repos(
+        &self,
+        #[tool(aggr)] param: ListTeamReposParam,
+    ) -> Result<CallToolResult, McpError> {
+        let path = format!("orgs/{}/teams/{}/repos", param.org, param.team_slug);
+        let result = self.api_list_all(&path, Vec::new()).await;
+        if !result.success {
+            return Err(org_command_error("Failed to get team repositories", &param.org, &result));
+        }
+        let mut repos: Vec<Value> = parse_json_lines(&result.output)?
+            .iter()
+            .map(|repo| {
+                json!({
+                    "name": repo["name"],
+                    "full_name": repo["full_name"],
+                    "permission": team_repo_permission(repo),
+                    "url": repo["html_url"],
+                })
+            })
+            .collect();
+        if let Some(limit) = param.limit {
+            repos.truncate(limit as usize);
+        }
+        Ok(command_success(Value::from(repos).to_string(), &result))
+    }
+
+    /// Check whether a user has access to a repository
+    #[tool(description = "Check whether a user is a collaborator of a repository and their permission level (admin, maintain, write, triage, read) and role name. Users without access report is_collaborator false rather than an error")]
+    async fn check_collaborator(
+        &self,
+        #[tool(aggr)] param: CheckCollaboratorParam,
+    ) -> Result<CallToolResult, McpError> {
+        let args = vec![
+            "api".to_string(),
+            format!("repos/{}/{}/collaborators/{}/permission", param.owner, param.repo, param.username),
+        ];
+        let result = self.run_gh_read(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        // 404 means not a collaborator; GitHub may also answer with permission "none"
+        let summary = if result.success {
+            let permission = parse_json_output(&result.output)?;
+            json!({
+                "username": param.username,
+                "is_collaborator": permission["permission"].as_str().is_some_and(|p| p != "none"),
+                "permission": permission["permission"],
+                "role_name": permission["role_name"],
+            })
+        } else if classify_error(&result) == ErrorKind::NotFound {
+            json!({"username": param.username, "is_collaborator": false, "permission": "none", "role_name": null})
+        } else {
+            return Err(command_error("Failed to check collaborator", &result));
+        };
+        Ok(CallToolResult::success(vec![Content::text(summary.to_string())]))
+    }
+
+    /// Grant a team access to a repository or remove it
+    #[tool(description = "Give an organization team a permission on a repository (pull, triage, push, maintain or admin), or remove the repository from the team with permission none. Requires team maintainer or organization owner rights")]
+    async fn set_team_repo_permission(
+        &self,
+        #[tool(aggr)] param: SetTeamRepoPermissionParam,
+    ) -> Result<CallToolResult, McpError> {
+        let path = format!("orgs/{}/teams/{}/repos/{}/{}", param.org, param.team_slug, param.owner, param.repo);
+        let args = match param.permission {
+            TeamRepoPermission::None => vec!["api".to_string(), "--method".to_string(), "DELETE".to_string(), path],
+            permission => vec![
+                "api".to_string(),
+                "--method".to_string(),
+                "PUT".to_string(),
+                path,
+                "-f".to_string(),
+                format!("permission={}", permission.as_str()),
+            ],
+        };
+        let result = self.run_gh_command(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if result.success {
+            let summary = json!({
+                "team": param.team_slug,
+                "repo": format!("{}/{}", param.owner, param.repo),
+                "permission": param.permission.as_str(),
+            });
+            Ok(command_success(summary.to_string(), &result))
+        } else {
+            Err(command_error("Failed to set team repository permission", &result))
+        }
+    }
+
+    /// List deploy keys of a repository
+    #[tool(description = "List deploy keys of a repository with id, title, read_only, verified and created_at")]
+    async fn list_deploy_keys(
+        &self,
+        #[tool(aggr)] param: RepoParam,
+    ) -> Result<CallToolResult, McpError> {
+        let path = format!("repos/{}/{}/keys", param.owner, param.repo);
+        let result = self.api_list_all(&path, Vec::new()).await;
+        if !result.success {
+            return Err(command_error("Failed to get deploy keys", &result));
+        }
+        let keys: Vec<Value> = parse_json_lines(&result.output)?
+            .iter()
+            .map(|key| {
+                json!({
+                    "id": key["id"],
+                    "title": key["title"],
+                    "read_only": key["read_only"],
+                    "verified": key["verified"],
+                    "created_at": key["created_at"],
+                })
+            })
+            .collect();
+        Ok(command_success(Value::from(keys).to_string(), &result))
+    }
+
+    /// Add a deploy key to a repository
+    #[tool(description = "Add an SSH deploy key to a repository, read-only unless read_only is false. The key must be an SSH public key and is sent over stdin, never logged. Returns the created key id and title")]
+    async fn add_deploy_key(
+        &self,
+        #[tool(aggr)] param: AddDeployKeyParam,
+    ) -> Result<CallToolResult, McpError> {
+        let key = validate_ssh_public_key(&param.key)?;
+        let body = json!({
+            "title": param.title,
+            "key": key,
+            "read_only": param.read_only.unwrap_or(true),
+        });
+        let args = vec![
+            "api".to_string(),
+            "--method".to_string(),
+            "POST".to_string(),
+            format!("repos/{}/{}/keys", param.owner, param.repo),
+            "--input".to_string(),
+            "-".to_string(),
+        ];
+        let result = self.run_gh_command_with_input(args, body.to_string()).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if !result.success {
+            return Err(key_command_error("Failed to add deploy key", &result));
+        }
+        let created = parse_json_output(&result.output)?;
+        let summary = json!({
+            "id": created["id"],
+            "title": created["title"],
+            "read_only": created["read_only"],
+        });
+        Ok(command_success(summary.to_string(), &result))
+    }
+
+    /// List SSH keys of the authenticated user
+    #[tool(description = "List SSH keys of the authenticated user with id, title, key type and created_at. Needs the admin:public_key scope")]
+    async fn list_ssh_keys(&self) -> Result<CallToolResult, McpError> {
+        let result = self.api_list_all("user/keys", Vec::new()).await;
+        if !result.success {
+            return Err(command_error("Failed to get SSH keys", &result));
+        }
+        let keys: Vec<Value> = parse_json_lines(&result.output)?
+            .iter()
+            .map(|key| {
+                json!({
+                    "id": key["id"],
+                    "title": key["title"],
+                    "type": key["key"].as_str().and_then(|key| key.split_whitespace().next()),
+                    "created_at": key["created_at"],
+                })
+            })
+            .collect();
+        Ok(command_success(Value::from(keys).to_string(), &result))
+    }
+
+    /// Add an SSH key to the authenticated user
+    #[tool(description = "Add an SSH authentication key to the authenticated user. The key must be an SSH public key (ssh-ed25519, ssh-rsa, ecdsa or security key types) and is sent over stdin, never logged. Needs the admin:public_key scope")]
+    async fn add_ssh_key(
+        &self,
+        #[tool(aggr)] param: AddKeyParam,
+    ) -> Result<CallToolResult, McpError> {
+        let key = validate_ssh_public_key(&param.key)?;
+        let args = vec![
+            "ssh-key".to_string(),
+            "add".to_string(),
+            "--title".to_string(),
+            param.title.clone(),
+        ];
+        let result = self.run_gh_command_with_input(args, key.to_string()).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if !result.success {
+            return Err(key_command_error("Failed to add SSH key", &result));
+        }
+        // gh succeeds without uploading when the account already has the key
+        let already_added = result.stderr.as_deref().is_some_and(|e| e.contains("already exists"));
+        let summary = json!({"title": param.title, "added": !already_added});
+        Ok(command_success(summary.to_string(), &result))
+    }
+
+    /// Delete an SSH key of the authenticated user
+    #[tool(description = "Delete an SSH key of the authenticated user by ID. Requires confirm")]
+    async fn delete_ssh_key(
+        &self,
+        #[tool(aggr)] param: DeleteSshKeyParam,
+    ) -> Result<CallToolResult, McpError> {
+        require_confirm(param.confirm, "delete the SSH key")?;
+        let args = vec![
+            "ssh-key".to_string(),
+            "delete".to_string(),
+            param.id.to_string(),
+            "--yes".to_string(),
+        ];
+        let result = self.run_gh_command(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if result.success {
+            Ok(command_success(format!("SSH key {} deleted", param.id), &result))
+        } else {
+            Err(command_error("Failed to delete SSH key", &result))
+        }
+    }
+
+    /// List GPG keys of the authenticated user
+    #[tool(description = "List GPG keys of the authenticated user with id, key_id, emails, can_sign and expires_at. Needs the read:gpg_key scope")]
+    async fn list_gpg_keys(&self) -> Result<CallToolResult, McpError> {
+        let result = self.api_list_all("user/gpg_keys", Vec::new()).await;
+        if !result.success {
+            return Err(command_error("Failed to get GPG keys", &result));
+        }
+        let keys: Vec<Value> = parse_json_lines(&result.output)?
+            .iter()
+            .map(|key| {
+                let emails: Vec<&Value> = key["emails"]
+                    .as_array()
+                    .map(|emails| emails.iter().map(|email| &email["email"]).collect())
+                    .unwrap_or_default();
+                json!({
+                    "id": key["id"],
+                    "key_id": key["key_id"],
+                    "name": key["name"],
+                    "emails": emails,
+                    "can_sign": key["can_sign"],
+                    "created_at": key["created_at"],
+                    "expires_at": key["expires_at"],
+                })
+            })
+            .collect();
+        Ok(command_success(Value::from(keys).to_string(), &result))
+    }
+
+    /// Add a GPG key to the authenticated user
+    #[tool(description = "Add a GPG key to the authenticated user for commit signature verification. The key must be an ASCII-armored public key block and is sent over stdin, never logged. Needs the write:gpg_key scope")]
+    async fn add_gpg_key(
+        &self,
+        #[tool(aggr)] param: AddKeyParam,
+    ) -> Result<CallToolResult, McpError> {
+        let key = validate_gpg_public_key(&param.key)?;
+        let args = vec![
+            "gpg-key".to_string(),
+            "add".to_string(),
+            "--title".to_string(),
+            param.title.clone(),
+        ];
+        let result = self.run_gh_command_with_input(args, key.to_string()).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if result.success {
+            let summary = json!({"title": param.title, "added": true});
+            Ok(command_success(summary.to_string(), &result))
+        } else {
+            Err(key_command_error("Failed to add GPG key", &result))
+        }
+    }
+
+    /// Delete a GPG key of the authenticated user
+    #[tool(description = "Delete a GPG key of the authenticated user by key_id. Requires confirm")]
+    async fn delete_gpg_key(
+        &self,
+        #[tool(aggr)] param: DeleteGpgKeyParam,
+    ) -> Result<CallToolResult, McpError> {
+        require_confirm(param.confirm, "delete the GPG key")?;
+        let args = vec![
+            "gpg-key".to_string(),
+            "delete".to_string(),
+            param.key_id.clone(),
+            "--yes".to_string(),
+        ];
+        let result = self.run_gh_command(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if result.success {
+            Ok(command_success(format!("GPG key {} deleted", param.key_id), &result))
+        } else {
+            Err(command_error("Failed to delete GPG key", &result))
+        }
+    }
+
+    /// List webhooks of a repository
+    #[tool(description = "List webhooks of a repository with id, delivery url, content type, events, active and whether a secret is set. Secrets are never returned")]
+    async fn list_webhooks(
+        &self,
+        #[tool(aggr)] param: RepoParam,
+    ) -> Result<CallToolResult, McpError> {
+        let path = format!("repos/{}/{}/hooks", param.owner, param.repo);
+        let result = self.api_list_all(&path, Vec::new()).await;
+        if !result.success {
+            return Err(command_error("Failed to get webhooks", &result));
+        }
+        let hooks: Vec<Value> = parse_json_lines(&result.output)?.iter().map(shape_webhook).collect();
+        Ok(command_success(Value::from(hooks).to_string(), &result))
+    }
+
+    /// Create a webhook on a repository
+    #[tool(description = "Create a webhook on a repository, active unless active is false, delivering the given events to an HTTPS URL. The request is sent over stdin so the secret is never logged, and the returned webhook leaves it out")]
+    async fn create_webhook(
+        &self,
+        #[tool(aggr)] param: CreateWebhookParam,
+    ) -> Result<CallToolResult, McpError> {
+        validate_webhook(&param.url, &param.events)?;
+        let mut config = json!({
+            "url": param.url,
+            "content_type": param.content_type.unwrap_or(WebhookContentType::Json).as_str(),
+            "insecure_ssl": "0",
+        });
+        if let Some(secret) = &param.secret {
+            config["secret"] = json!(secret);
+        }
+        let body = json!({
+            "name": "web",
+            "active": param.active.unwrap_or(true),
+            "events": param.events,
+            "config": config,
+        });
+        let args = vec![
+            "api".to_string(),
+            "--method".to_string(),
+            "POST".to_string(),
+            format!("repos/{}/{}/hooks", param.owner, param.repo),
+            "--input".to_string(),
+            "-".to_string(),
+        ];
+        let result = self.run_gh_command_with_input(args, body.to_string()).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if !result.success {
+            return Err(command_error("Failed to create webhook", &result));
+        }
+        let hook = parse_json_output(&result.output)?;
+        Ok(command_success(shape_webhook(&hook).to_string(), &result))
+    }
+
+    /// Delete a webhook of a repository
+    #[tool(description = "Delete a webhook of a repository by ID. Requires confirm")]
+    async fn delete_webhook(
+        &self,
+        #[tool(aggr)] param: DeleteWebhookParam,
+    ) -> Result<CallToolResult, McpError> {
+        require_confirm(param.confirm, "delete the webhook")?;
+        let args = vec![
+            "api".to_string(),
+            "--method".to_string(),
+            "DELETE".to_string(),
+            format!("repos/{}/{}/hooks/{}", param.owner, param.repo, param.hook_id),
+        ];
+        let result = self.run_gh_command(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if result.success {
+            Ok(command_success(format!("Webhook {} deleted", param.hook_id), &result))
+        } else {
+            Err(command_error("Failed to delete webhook", &result))
+        }
+    }
+
+    /// Query the audit log of an organization
+    #[tool(description = "Query the audit log of an organization, newest first, filtered by search phrase, action, actor and date range. Returns timestamp, actor, action and target per event with the raw event under detail. Requires GitHub Enterprise Cloud and an organization owner token")]
+    async fn org_audit_log(
+        &self,
+        #[tool(aggr)] param: OrgAuditLogParam,
+    ) -> Result<CallToolResult, McpError> {
+        let phrase = audit_log_phrase(&param)?;
+        let limit = param.limit.unwrap_or(100).clamp(1, 1000) as usize;
+        let mut events = Vec::new();
+        let mut page = 1;
+        loop {
+            let mut args = vec![
+                "api".to_string(),
+                "--method".to_string(),
+                "GET".to_string(),
+                format!("orgs/{}/audit-log", param.org),
+                "-f".to_string(),
+                "include=all".to_string(),
+                "-f".to_string(),
+                "order=desc".to_string(),
+                "-F".to_string(),
+                format!("per_page={}", AUDIT_LOG_PAGE_SIZE),
+                "-F".to_string(),
+                format!("page={}", page),
+            ];
+            if !phrase.is_empty() {
+                args.push("-f".to_string());
+                args.push(format!("phrase={}", phrase));
+            }
+            let result = self.run_gh_read(args).await;
+            
+            let mut last_result = self.last_result.lock().await;
+            *last_result = Some(result.clone());
+            drop(last_result);
+            
+            if !result.success {
+                return Err(audit_log_error(&param.org, &result));
+            }
+            let batch = parse_json_output(&result.output)?.as_array().cloned().unwrap_or_default();
+            let exhausted = batch.len() < AUDIT_LOG_PAGE_SIZE as usize;
+            events.extend(batch.iter().map(shape_audit_event));
+            if exhausted || events.len() >= limit {
+                events.truncate(limit);
+                return Ok(command_success(Value::from(events).to_string(), &result));
+            }
+            page += 1;
+        }
+    }
+
+    /// Generate release notes with GitHub's automatic changelog
+    #[tool(description = "Generate release notes for a tag with GitHub's automatically generated changelog (merged pull requests, new contributors, full changelog link), using .github/release.yml when present. Returns the suggested name and Markdown body without creating a release")]
+    async fn generate_release_notes(
+        &self,
+        #[tool(aggr)] param: GenerateReleaseNotesParam,
+    ) -> Result<CallToolResult, McpError> {
+        let mut args = vec![
+            "api".to_string(),
+            "--method".to_string(),
+            "POST".to_string(),
+            format!("repos/{}/{}/releases/generate-notes", param.owner, param.repo),
+            "-f".to_string(),
+            format!("tag_name={}", param.tag),
+        ];
+        if let Some(previous_tag) = &param.previous_tag {
+            args.push("-f".to_string());
+            args.push(format!("previous_tag_name={}", previous_tag));
+        }
+        let result = self.run_gh_command(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if !result.success {
+            return Err(command_error("Failed to generate release notes", &result));
+        }
+        let notes = parse_json_output(&result.output)?;
+        let summary = json!({"name": notes["name"], "body": notes["body"]});
+        Ok(command_success(summary.to_string(), &result))
+    }
+
+    /// Summarize what changed between two releases
+    #[tool(description = "Compare two releases of a repository: both releases' notes and the commits between their tags, grouped by the pull request that merged them (from merge and squash commit messages), with the remaining commits listed separately. GitHub returns at most 250 commits of the range")]
+    async fn release_diff(
+        &self,
+        #[tool(aggr)] param: ReleaseDiffParam,
+    ) -> Result<CallToolResult, McpError> {
+        let from = self.release_by_tag(&param.owner, &param.repo, &param.from_tag).await?;
+        let to = self.release_by_tag(&param.owner, &param.repo, &param.to_tag).await?;
+        let args = vec![
+            "api".to_string(),
+            format!("repos/{}/{}/compare/{}...{}", param.owner, param.repo, param.from_tag, param.to_tag),
+        ];
+        let result = self.run_gh_read(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if !result.success {
+            return Err(command_error("Failed to compare releases", &result));
+        }
+        let comparison = parse_json_output(&result.output)?;
+        let commits = comparison["commits"].as_array().cloned().unwrap_or_default();
+        let mut pull_requests: Vec<Value> = Vec::new();
+        let mut other_commits = Vec::new();
+        for commit in &commits {
+            let message = commit["commit"]["message"].as_str().unwrap_or_default();
+            match pull_request_of_commit(message) {
+                Some((number, title)) => match pull_requests.iter_mut().find(|pr| pr["number"] == json!(number)) {
+                    Some(pr) => pr["commits"] = json!(pr["commits"].as_u64().unwrap_or_default() + 1),
+                    None => pull_requests.push(json!({"number": number, "title": title, "commits": 1})),
+                },
+                None => other_commits.push(json!({
+                    "sha": commit["sha"].as_str().map(|sha| &sha[..sha.len().min(7)]),
+                    "message": message.lines().next(),
+                    "author": commit["author"]["login"],
+                })),
+            }
+        }
+        let total_commits = comparison["total_commits"].as_u64().unwrap_or_default();
+        let summary = json!({
+            "from": from,
+            "to": to,
+            "total_commits": total_commits,
+            "truncated": total_commits > commits.len() as u64,
+            "pull_requests": pull_requests,
+            "other_commits": other_commits,
+        });
+        Ok(command_success(summary.to_string(), &result))
+    }
+
+    /// List organization secrets
+    #[tool(description = "List secrets of an organization with their visibility and, for selected visibility, the number of repositories they are shared with (or an error if it could not be counted). Values are never returned. The app selects the store: actions (default), dependabot or codespaces")]
+    async fn list_org_secrets(
+        &self,
+        #[tool(aggr)] param: ListOrgSecretsParam,
+    ) -> Result<CallToolResult, McpError> {
+        let app = param.app.unwrap_or(SecretApp::Actions).as_str();
+        let args = vec![
+            "api".to_string(),
+            "--paginate".to_string(),
+            format!("orgs/{}/{}/secrets", param.org, app),
+            "--jq".to_string(),
+            ".secrets[]".to_string(),
+        ];
+        let result = self.run_gh_read(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        drop(last_result);
+        
+        if !result.success {
+            return Err(command_error("Failed to get organization secrets list", &result));
+        }
+        
+        let mut secrets = Vec::new();
+        for secret in parse_json_lines(&result.output)? {
+            let mut entry = json!({
+                "name": secret["name"],
+                "visibility": secret["visibility"],
+                "selected_repos": null,
+                "updated_at": secret["updated_at"],
+            });
+            if secret["visibility"] == "selected" {
+                let name = secret["name"].as_str().unwrap_or_default();
+                let args = vec![
+                    "api".to_string(),
+                    format!("orgs/{}/{}/secrets/{}/repositories", param.org, app, name),
+                    "--jq".to_string(),
+                    ".total_count".to_string(),
+                ];
+                let count = self.run_gh_read(args).await;
+                match count.output.trim().parse::<u64>() {
+                    Ok(selected_repos) if count.success => entry["selected_repos"] = selected_repos.into(),
+                    // Report the failure instead of a null count that reads like "no repositories"
+                    _ => entry["error"] = count.error.unwrap_or_default().into(),
+                }
+            }
+            secrets.push(entry);
+        }
+        Ok(command_success(Value::from(secrets).to_string(), &result))
+    }
+
+    /// Set an organization secret
+    #[tool(description = "Create or update an organization secret shared with all, private, or selected repositories (repos required for selected). The value is passed to gh on stdin and never recorded. The app selects the store: actions (default), dependabot or codespaces")]
+    async fn set_org_secret(
+        &self,
+        #[tool(aggr)] param: SetOrgSecretParam,
+    ) -> Result<CallToolResult, McpError> {
+        validate_secret_name(&param.name)?;
+        let repos = match (param.visibility, param.repos.unwrap_or_default()) {
+            (SecretVisibility::Selected, repos) if repos.is_empty() => {
+                return Err(McpError::invalid_params(
+                    "repos is required when visibility is selected",
+                    None,
+                ));
+            }
+            (SecretVisibility::Selected, repos) => self.ensure_org_repos_exist(&param.org, &repos).await?,
+            (_, repos) if !repos.is_empty() => {
+                return Err(McpError::invalid_params(
+                    "repos can only be set when visibility is selected",
+                    None,
+                ));
+            }
+            _ => Vec::new(),
+        };
+        
+        let mut args = vec![
+            "secret".to_string(),
+            "set".to_string(),
+            param.name.clone(),
+            "--org".to_string(),
+            param.org.clone(),
+            "--visibility".to_string(),
+            param.visibility.as_str().to_string(),
+        ];
+        if !repos.is_empty() {
+            args.push("--repos".to_string());
+            args.push(repos.join(","));
+        }
+        args.extend(secret_app_args(param.app));
+        let result = redact_secret(
+            &self.run_gh_command_with_input(args, param.value.clone()).await,
+            &param.value,
+        );
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if result.success {
+            let summary = json!({
+                "name": param.name,
+                "org": param.org,
+                "visibility": param.visibility.as_str(),
+                "repos": repos,
+            });
+            Ok(command_success(summary.to_string(), &result))
+        } else {
+            Err(command_error("Failed to set organization secret", &result))
+        }
+    }
+
+    /// List deployment environments
+    #[tool(description = "List deployment environments of a repository with required reviewers, wait timer and deployment branch policy")]
+    async fn list_environments(
+        &self,
+        #[tool(aggr)] param: RepoParam,
+    ) -> Result<CallToolResult, McpError> {
+        let args = vec![
+            "api".to_string(),
+            format!("repos/{}/{}/environments", param.owner, param.repo),
+        ];
+        let result = self.run_gh_read(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if !result.success {
+            return Err(command_error("Failed to get environments list", &result));
+        }
+        
+        let body = parse_json_output(&result.output)?;
+        let environments: Vec<Value> = body["environments"]
+            .as_array()
+            .map(|envs| envs.iter().map(shape_environment).collect())
+            .unwrap_or_default();
+        Ok(command_success(Value::from(environments).to_string(), &result))
+    }
+
+    /// Set an environment secret
+    #[tool(description = "Create or update a deployment environment secret. The value is passed to gh on stdin and never recorded")]
+    async fn set_env_secret(
+        &self,
+        #[tool(aggr)] param: SetEnvSecretParam,
+    ) -> Result<CallToolResult, McpError> {
+        validate_secret_name(&param.name)?;
+        self.ensure_environment_exists(&param.owner, &param.repo, &param.env).await?;
+        let mut args = vec!["secret".to_string(), "set".to_string(), param.name.clone()];
+        args.extend(scope_args(&param.owner, &param.repo, Some(&param.env)));
+        let result = redact_secret(
+            &self.run_gh_command_with_input(args, param.value.clone()).await,
+            &param.value,
+        );
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if result.success {
+            Ok(command_success(format!("Set secret {} in environment {}", param.name, param.env), &result))
+        } else {
+            Err(command_error("Failed to set secret", &result))
+        }
+    }
+
+    /// Delete an environment secret
+    #[tool(description = "Delete a deployment environment secret. Requires confirm set to true")]
+    async fn delete_env_secret(
+        &self,
+        #[tool(aggr)] param: DeleteEnvSecretParam,
+    ) -> Result<CallToolResult, McpError> {
+        require_confirm(param.confirm, "delete the secret")?;
+        let mut args = vec!["secret".to_string(), "delete".to_string(), param.name.clone()];
+        args.extend(scope_args(&param.owner, &param.repo, Some(&param.env)));
+        let result = self.run_gh_command(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if result.success {
+            Ok(command_success(format!("Deleted secret {} from environment {}", param.name, param.env), &result))
+        } else {
+            Err(command_error("Failed to delete secret", &result))
+        }
+    }
+
+    /// List Actions variables
+    #[tool(description = "List Actions variables with their values of a repository or one of its environments")]
+    async fn list_variables(
+        &self,
+        #[tool(aggr)] param: ListVariablesParam,
+    ) -> Result<CallToolResult, McpError> {
+        if let Some(env) = &param.env {
+            self.ensure_environment_exists(&param.owner, &param.repo, env).await?;
+        }
+        let mut args = vec!["variable".to_string(), "list".to_string()];
+        args.extend(scope_args(&param.owner, &param.repo, param.env.as_deref()));
+        args.push("--json".to_string());
+        args.push("name,value,updatedAt".to_string());
+        let result = self.run_gh_read(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if result.success {
+            Ok(command_success(result.output.clone(), &result))
+        } else {
+            Err(command_error("Failed to get variables list", &result))
+        }
+    }
+
+    /// Create or update an Actions variable
+    #[tool(description = "Create or update an Actions variable of a repository or one of its environments. Reports whether the variable was created or updated")]
+    async fn set_variable(
+        &self,
+        #[tool(aggr)] param: SetVariableParam,
+    ) -> Result<CallToolResult, McpError> {
+        if let Some(env) = &param.env {
+            self.ensure_environment_exists(&param.owner, &param.repo, env).await?;
+        }
+        let scope = scope_args(&param.owner, &param.repo, param.env.as_deref());
+        
+        let mut list_args = vec!["variable".to_string(), "list".to_string()];
+        list_args.extend(scope.clone());
+        list_args.push("--json".to_string());
+        list_args.push("name".to_string());
+        let existing = self.run_gh_read(list_args).await;
+        if !existing.success {
+            return Err(command_error("Failed to get variables list", &existing));
+        }
+        let exists = parse_json_output(&existing.output)?
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|var| var["name"].as_str())
+            .any(|name| name.eq_ignore_ascii_case(&param.name));
+        
+        let mut args = vec!["variable".to_string(), "set".to_string(), param.name.clone()];
+        args.extend(scope);
+        args.push("--body".to_string());
+        args.push(param.value);
+        let result = self.run_gh_command(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if result.success {
+            let summary = json!({
+                "name": param.name,
+                "env": param.env,
+                "action": if exists { "updated" } else { "created" },
+            });
+            Ok(command_success(summary.to_string(), &result))
+        } else {
+            Err(command_error("Failed to set variable", &result))
+        }
+    }
+
+    /// Delete an Actions variable
+    #[tool(description = "Delete an Actions variable of a repository or one of its environments")]
+    async fn delete_variable(
+        &self,
+        #[tool(aggr)] param: DeleteVariableParam,
+    ) -> Result<CallToolResult, McpError> {
+        if let Some(env) = &param.env {
+            self.ensure_environment_exists(&param.owner, &param.repo, env).await?;
+        }
+        let mut args = vec!["variable".to_string(), "delete".to_string(), param.name.clone()];
+        args.extend(scope_args(&param.owner, &param.repo, param.env.as_deref()));
+        let result = self.run_gh_command(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if result.success {
+            Ok(command_success(format!("Deleted variable {}", param.name), &result))
+        } else {
+            Err(command_error("Failed to delete variable", &result))
+        }
+    }
+
+    /// List commits of a repository
+    #[tool(description = "List commits of specified repository, optionally filtered by branch, path, author or date range. Paginated with per_page and page; follow next_page for more")]
+    async fn list_commits(
+        &self,
+        #[tool(aggr)] param: ListCommitsParam,
+    ) -> Result<CallToolResult, McpError> {
+        let filters = [
+            ("sha", param.sha),
+            ("path", param.path),
+            ("author", param.author),
+            ("since", param.since),
+            ("until", param.until),
+        ];
+        let query = filters
+            .into_iter()
+            .filter_map(|(name, value)| value.map(|value| (name, value)))
+            .collect();
+        let (summary, result) = self
+            .api_page(
+                &format!("repos/{}/{}/commits", param.owner, param.repo),
+                query,
+                &param.page,
+                shape_commit,
+                "Failed to get commits list",
+            )
+            .await?;
+        Ok(command_success(summary.to_string(), &result))
+    }
+
+    /// List branches of a repository
+    #[tool(description = "List branches of specified repository with head SHA and protection status. Paginated with per_page and page; follow next_page for more")]
+    async fn list_branches(
+        &self,
+        #[tool(aggr)] param: ListBranchesParam,
+    ) -> Result<CallToolResult, McpError> {
+        let query = param
+            .protected
+            .map(|protected| vec![("protected", protected.to_string())])
+            .unwrap_or_default();
+        let (summary, result) = self
+            .api_page(
+                &format!("repos/{}/{}/branches", param.owner, param.repo),
+                query,
+                &param.page,
+                shape_branch,
+                "Failed to get branches list",
+            )
+            .await?;
+        Ok(command_success(summary.to_string(), &result))
+    }
+
+    /// List contributors of a repository
+    #[tool(description = "List contributors of specified repository with their login and contribution count, most active first. Paginated with per_page and page; follow next_page for more")]
+    async fn list_contributors(
+        &self,
+        #[tool(aggr)] param: ListContributorsParam,
+    ) -> Result<CallToolResult, McpError> {
+        let (mut summary, result) = self
+            .api_page(
+                &format!("repos/{}/{}/contributors", param.owner, param.repo),
+                Vec::new(),
+                &param.page,
+                shape_contributor,
+                "Failed to get contributors list",
+            )
+            .await?;
+        // The API already orders by contributions, but don't rely on it
+        if let Some(items) = summary["items"].as_array_mut() {
+            items.sort_by_key(|item| std::cmp::Reverse(item["contributions"].as_u64().unwrap_or(0)));
+        }
+        Ok(command_success(summary.to_string(), &result))
+    }
+
+    /// Resolve a branch or tag to a commit SHA
+    #[tool(description = "Resolve a branch or tag name to its commit SHA and commit date, for chaining into tools that need a SHA")]
+    async fn resolve_ref(
+        &self,
+        #[tool(aggr)] param: ResolveRefParam,
+    ) -> Result<CallToolResult, McpError> {
+        let args = vec![
+            "api".to_string(),
+            format!("repos/{}/{}/commits/{}", param.owner, param.repo, param.ref_name),
+        ];
+        let result = self.run_gh_read(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if !result.success {
+            // Unknown refs come back as 404, or as 422 "No commit found" for SHA-like names
+            let unknown_ref = result.error.as_deref().is_some_and(|e| e.contains("No commit found"));
+            if unknown_ref || classify_error(&result) == ErrorKind::NotFound {
+                return Err(McpError::resource_not_found(
+                    format!("ref {} not found in {}/{}", param.ref_name, param.owner, param.repo),
+                    Some(command_error_data(&result)),
+                ));
+            }
+            return Err(command_error("Failed to resolve ref", &result));
+        }
+        
+        let commit = parse_json_output(&result.output)?;
+        let resolved = json!({
+            "ref": param.ref_name,
+            "sha": commit["sha"],
+            "date": commit["commit"]["committer"]["date"],
+        });
+        Ok(command_success(resolved.to_string(), &result))
+    }
+
+    /// Get repository traffic
+    #[tool(description = "Get 14-day view and clone counts of a repository. Requires push access to the repository")]
+    async fn repo_traffic(
+        &self,
+        #[tool(aggr)] param: RepoParam,
+    ) -> Result<CallToolResult, McpError> {
+        let (views, _) = self.traffic(&param, "views").await?;
+        let (clones, result) = self.traffic(&param, "clones").await?;
+        let traffic = json!({"views": views, "clones": clones});
+        Ok(command_success(traffic.to_string(), &result))
+    }
+
+    /// List files changed by a pull request
+    #[tool(description = "List files changed by a pull request with change status and line counts. Paginated with per_page and page; follow next_page for more")]
+    async fn pr_files(
+        &self,
+        #[tool(aggr)] param: PRFilesParam,
+    ) -> Result<CallToolResult, McpError> {
+        let (summary, result) = self
+            .api_page(
+                &format!("repos/{}/{}/pulls/{}/files", param.owner, param.repo, param.number),
+                Vec::new(),
+                &param.page,
+                shape_pr_file,
+                "Failed to get pull request files",
+            )
+            .await?;
+        Ok(command_success(summary.to_string(), &result))
+    }
+
+    /// Get the unified diff of a pull request
+    #[tool(description = "Get the unified diff of a pull request as raw text")]
+    async fn get_pr_diff(
+        &self,
+        #[tool(aggr)] param: GetPRDiffParam,
+    ) -> Result<CallToolResult, McpError> {
+        let args = vec![
+            "api".to_string(),
+            format!("repos/{}/{}/pulls/{}", param.owner, param.repo, param.number),
+            "--header".to_string(),
+            "Accept: application/vnd.github.v3.diff".to_string(),
+        ];
+        let result = self.run_gh_read(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if result.success {
+            Ok(command_success(result.output.clone(), &result))
+        } else {
+            Err(command_error("Failed to get pull request diff", &result))
+        }
+    }
+
+    /// List reviews of a pull request
+    #[tool(description = "List all reviews of a pull request with reviewer, state (APPROVED, CHANGES_REQUESTED, COMMENTED, DISMISSED), body, submission time and URL")]
+    async fn list_pr_reviews(
+        &self,
+        #[tool(aggr)] param: ListPRReviewsParam,
+    ) -> Result<CallToolResult, McpError> {
+        let args = vec![
+            "api".to_string(),
+            "--paginate".to_string(),
+            format!("repos/{}/{}/pulls/{}/reviews", param.owner, param.repo, param.number),
+            "--jq".to_string(),
+            ".[]".to_string(),
+        ];
+        let result = self.run_gh_read(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if !result.success {
+            return Err(command_error("Failed to get pull request reviews", &result));
+        }
+        
+        let reviews: Vec<Value> = parse_json_lines(&result.output)?.iter().map(shape_review).collect();
+        Ok(command_success(Value::from(reviews).to_string(), &result))
+    }
+
+    /// Dismiss a pull request review
+    #[tool(description = "Dismiss a pull request review, e.g. a stale change request, with a required message. Returns the updated review")]
+    async fn dismiss_pr_review(
+        &self,
+        #[tool(aggr)] param: DismissPRReviewParam,
+    ) -> Result<CallToolResult, McpError> {
+        if param.message.trim().is_empty() {
+            return Err(McpError::invalid_params("message must not be empty", None));
+        }
+        let args = vec![
+            "api".to_string(),
+            "--method".to_string(),
+            "PUT".to_string(),
+            format!(
+                "repos/{}/{}/pulls/{}/reviews/{}/dismissals",
+                param.owner, param.repo, param.number, param.review_id
+            ),
+            "-f".to_string(),
+            format!("message={}", param.message),
+            "-f".to_string(),
+            "event=DISMISS".to_string(),
+        ];
+        let result = self.run_gh_command(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if !result.success {
+            return Err(command_error("Failed to dismiss review", &result));
+        }
+        
+        let review = shape_review(&parse_json_output(&result.output)?);
+        Ok(command_success(review.to_string(), &result))
+    }
+
+    /// List inline review comments of a pull request
+    #[tool(description = "List inline code review comments of a pull request with file path, line, body, author and URL. Regular conversation comments are not included. Paginated with per_page and page; follow next_page for more")]
+    async fn list_pr_review_comments(
+        &self,
+        #[tool(aggr)] param: ListPRReviewCommentsParam,
+    ) -> Result<CallToolResult, McpError> {
+        let (summary, result) = self
+            .api_page(
+                &format!("repos/{}/{}/pulls/{}/comments", param.owner, param.repo, param.number),
+                Vec::new(),
+                &param.page,
+                shape_review_comment,
+                "Failed to get pull request review comments",
+            )
+            .await?;
+        Ok(command_success(summary.to_string(), &result))
+    }
+
+    /// Reply to an inline review comment
+    #[tool(description = "Reply to an inline code review comment thread of a pull request, returning the URL of the new comment")]
+    async fn reply_pr_review_comment(
+        &self,
+        #[tool(aggr)] param: ReplyPRReviewCommentParam,
+    ) -> Result<CallToolResult, McpError> {
+        let args = vec![
+            "api".to_string(),
+            "--method".to_string(),
+            "POST".to_string(),
+            format!("repos/{}/{}/pulls/{}/comments", param.owner, param.repo, param.number),
+            "-f".to_string(),
+            format!("body={}", param.body),
+            "-F".to_string(),
+            format!("in_reply_to={}", param.comment_id),
+            "--jq".to_string(),
+            ".html_url".to_string(),
+        ];
+        let result = self.run_gh_command(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if result.success {
+            Ok(command_success(result.output.trim().to_string(), &result))
+        } else {
+            Err(command_error("Failed to reply to review comment", &result))
+        }
+    }
+
+    /// Edit an issue or pull request comment
+    #[tool(description = "Replace the body of a conversation comment on an issue or pull request, returning the comment URL")]
+    async fn edit_issue_comment(
+        &self,
+        #[tool(aggr)] param: EditCommentParam,
+    ) -> Result<CallToolResult, McpError> {
+        let args = vec![
+            "api".to_string(),
+            "--method".to_string(),
+            "PATCH".to_string(),
+            format!("repos/{}/{}/issues/comments/{}", param.owner, param.repo, param.comment_id),
+            "-f".to_string(),
+            format!("body={}", param.body),
+            "--jq".to_string(),
+            ".html_url".to_string(),
+        ];
+        let result = self.run_gh_command(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if result.success {
+            Ok(command_success(result.output.trim().to_string(), &result))
+        } else {
+            Err(command_error("Failed to edit comment", &result))
+        }
+    }
+
+    /// Delete an issue or pull request comment
+    #[tool(description = "Delete a conversation comment from an issue or pull request by comment ID. Requires confirm set to true")]
+    async fn delete_issue_comment(
+        &self,
+        #[tool(aggr)] param: DeleteCommentParam,
+    ) -> Result<CallToolResult, McpError> {
+        require_confirm(param.confirm, "delete the comment")?;
+        let args = vec![
+            "api".to_string(),
+            "--method".to_string(),
+            "DELETE".to_string(),
+            format!("repos/{}/{}/issues/comments/{}", param.owner, param.repo, param.comment_id),
+        ];
+        let result = self.run_gh_command(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if result.success {
+            Ok(command_success(format!("Deleted comment {}", param.comment_id), &result))
+        } else {
+            Err(command_error("Failed to delete comment", &result))
+        }
+    }
+
+    /// Log out of GitHub
+    #[tool(description = "Log out of a GitHub account to clear credentials, e.g. between sessions. Combine with auth_login to switch accounts")]
+    async fn auth_logout(
+        &self,
+        #[tool(aggr)] param: AuthLogoutParam,
+    ) -> Result<CallToolResult, McpError> {
+        self.forget_current_user().await;
+        // An explicit host keeps gh from prompting for which account to log out of
+        let host = param.github_host.unwrap_or_else(|| DEFAULT_GITHUB_HOST.to_string());
+        let mut args = vec!["auth".to_string(), "logout".to_string(), "--hostname".to_string(), host.clone()];
+        
+        if let Some(username) = &param.username {
+            args.push("--user".to_string());
+            args.push(username.clone());
+        }
+        
+        let result = self.run_gh_command(args).await;
+        self.forget_current_user().await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if result.success {
+            let confirmation = match param.username {
+                Some(username) => format!("Logged out account {} of {}", username, host),
+                None => format!("Logged out of {}", host),
+            };
+            Ok(command_success(confirmation, &result))
+        } else {
+            Err(command_error("Failed to log out", &result))
+        }
+    }
+
+    /// Switch the active account
+    #[tool(description = "Switch the active GitHub account among accounts already logged in with auth_login. Returns the now active user")]
+    async fn switch_account(
+        &self,
+        #[tool(aggr)] param: SwitchAccountParam,
+    ) -> Result<CallToolResult, McpError> {
+        self.forget_current_user().await;
+        let host = param.hostname.unwrap_or_else(|| DEFAULT_GITHUB_HOST.to_string());
+        let args = vec![
+            "auth".to_string(),
+            "switch".to_string(),
+            "--hostname".to_string(),
+            host.clone(),
+            "--user".to_string(),
+            param.user,
+        ];
+        let result = self.run_gh_command(args).await;
+        self.forget_current_user().await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if !result.success {
+            return Err(command_error("Failed to switch account", &result));
+        }
+        
+        let status_args = vec!["auth".to_string(), "status".to_string(), "--hostname".to_string(), host.clone()];
+        let status = self.run_gh_read(status_args).await;
+        *last_result = Some(status.clone());
+        
+        // gh prints the status report to stderr on older versions
+        let report = format!("{}{}", status.output, status.stderr.clone().unwrap_or_default());
+        let summary = json!({"host": host, "active_user": active_user_from_status(&report)});
+        Ok(command_success(summary.to_string(), &status))
+    }
+
+    /// Get the OAuth scopes granted to the current token
+    #[tool(description = "List OAuth scopes granted to the authenticated token, e.g. to explain which scope (repo, workflow, delete_repo) is missing after a permission error. Never returns the token itself")]
+    async fn token_scopes(&self) -> Result<CallToolResult, McpError> {
+        let args = vec!["api".to_string(), "-i".to_string(), "user".to_string()];
+        let result = self.run_gh_read(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if !result.success {
+            return Err(command_error("Failed to get token scopes", &result));
+        }
+        
+        let response = parse_api_response(&result.output);
+        let user = parse_json_output(&response.body)?;
+        // Fine-grained tokens and GitHub App tokens do not report OAuth scopes
+        let scopes = response.headers.get("x-oauth-scopes").map(|scopes| {
+            scopes
+                .split(',')
+                .map(str::trim)
+                .filter(|scope| !scope.is_empty())
+                .collect::<Vec<_>>()
+        });
+        let summary = match scopes {
+            Some(scopes) => json!({"user": user["login"], "scopes": scopes}),
+            None => json!({
+                "user": user["login"],
+                "scopes": null,
+                "message": "Token does not report OAuth scopes (fine-grained or app token); permissions are configured per repository",
+            }),
+        };
+        Ok(command_success(summary.to_string(), &result))
+    }
+
+    /// Get the result of the most recent command
+    #[tool(description = "Get the full result (output, stderr, exit code) of the most recently executed GitHub CLI command, for debugging failures")]
+    async fn get_last_result(&self) -> Result<CallToolResult, McpError> {
+        let last_result = self.last_result.lock().await.clone();
+        match last_result {
+            Some(result) => Ok(CallToolResult::success(vec![Content::json(result)?])),
+            None => Ok(CallToolResult::success(vec![Content::text("No previous result")])),
+        }
+    }
+
+    /// Get the remaining API quota per rate limit resource
+    #[tool(description = "Get the remaining GitHub API quota for the core, search, graphql and code_search resources: limit, remaining, used and reset as epoch seconds and ISO time. Call it before bulk operations to check they fit the quota. Answers instantly from the quota seen on recent API responses when still valid; set refresh to query the API, which does not count against the quota")]
+    async fn rate_limit(
+        &self,
+        #[tool(aggr)] param: RateLimitParam,
+    ) -> Result<CallToolResult, McpError> {
+        if !param.refresh {
+            let rate_limits = self.rate_limits.lock().await;
+            let now = Utc::now().timestamp() as u64;
+            // A partial snapshot, e.g. only core after REST calls, must not hide the other quotas
+            let resources: Option<serde_json::Map<String, Value>> = RATE_LIMIT_RESOURCES
+                .iter()
+                .map(|name| {
+                    rate_limits
+                        .get(*name)
+                        .filter(|entry| entry["reset"].as_u64().is_some_and(|reset| reset > now))
+                        .map(|entry| (name.to_string(), entry.clone()))
+                })
+                .collect();
+            if let Some(resources) = resources {
+                let summary = json!({"source": "recent responses", "resources": resources});
+                return Ok(CallToolResult::success(vec![Content::text(summary.to_string())]));
+            }
+        }
+        
+        let args = vec!["api".to_string(), "rate_limit".to_string()];
+        let result = self.run_gh_read(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if !result.success {
+            return Err(command_error("Failed to get rate limit", &result));
+        }
+        let body = parse_json_output(&result.output)?;
+        let mut rate_limits = self.rate_limits.lock().await;
+        for name in RATE_LIMIT_RESOURCES {
+            let resource = &body["resources"][name];
+            if resource.is_object() {
+                let entry = rate_limit_entry(
+                    resource["limit"].as_u64(),
+                    resource["remaining"].as_u64(),
+                    resource["used"].as_u64(),
+                    resource["reset"].as_u64(),
+                );
+                rate_limits.insert(name.to_string(), entry);
+            }
+        }
+        let resources: serde_json::Map<String, Value> = RATE_LIMIT_RESOURCES
+            .iter()
+            .filter_map(|name| rate_limits.get(*name).map(|entry| (name.to_string(), entry.clone())))
+            .collect();
+        let summary = json!({"source": "api", "resources": resources});
+        Ok(command_success(summary.to_string(), &result))
+    }
+
+    /// Get invocation metrics of the server's tools
+    #[tool(description = "Get in-memory metrics since the server started: per tool, the number of calls, successes and failures, the average latency and the recent (exponentially weighted) latency in milliseconds")]
+    async fn metrics(&self) -> Result<CallToolResult, McpError> {
+        Ok(CallToolResult::success(vec![Content::text(self.metrics.snapshot().to_string())]))
+    }
+
+    /// Get GitHub authentication status
+    #[tool(description = "Check GitHub CLI authentication status")]
+    async fn auth_status(&self) -> Result<CallToolResult, McpError> {
+        let args = vec!["auth".to_string(), "status".to_string()];
+        let result = self.run_gh_read(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        Ok(command_success(result.output.clone(), &result))
+    }
+
+    /// List Projects (v2) of a user or organization
+    #[tool(description = "List Projects (v2) of a user or organization with number, title, URL, state and item count. Requires the project token scope")]
+    async fn list_projects(
+        &self,
+        #[tool(aggr)] param: ListProjectsParam,
+    ) -> Result<CallToolResult, McpError> {
+        let args = vec![
+            "project".to_string(),
+            "list".to_string(),
+            "--owner".to_string(),
+            project_owner(param.owner, param.is_org)?,
+            "--limit".to_string(),
+            param.limit.unwrap_or(30).to_string(),
+            "--format".to_string(),
+            "json".to_string(),
+        ];
+        let result = self.run_gh_read(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if !result.success {
+            return Err(project_command_error("Failed to get projects list", &result));
+        }
+        
+        let body = parse_json_output(&result.output)?;
+        let projects: Vec<Value> = body["projects"]
+            .as_array()
+            .map(|projects| {
+                projects
+                    .iter()
+                    .map(|p| {
+                        json!({
+                            "number": p["number"],
+                            "title": p["title"],
+                            "url": p["url"],
+                            "closed": p["closed"],
+                            "public": p["public"],
+                            "items": p["items"]["totalCount"],
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        Ok(command_success(Value::from(projects).to_string(), &result))
+    }
+
+    /// List items of a Project (v2)
+    #[tool(description = "List items of a Project (v2) as flat rows: item id, type (Issue, PullRequest or DraftIssue), title, number, repository, URL and field values such as Status, Iteration and custom fields. Optionally filter on Status. Requires the project token scope")]
+    async fn project_items(
+        &self,
+        #[tool(aggr)] param: ProjectItemsParam,
+    ) -> Result<CallToolResult, McpError> {
+        let owner = param.owner.unwrap_or_else(|| "@me".to_string());
+        let (items, result) = self
+            .fetch_project_items(&owner, param.number, param.limit.unwrap_or(30), param.status.as_deref())
+            .await?;
+        Ok(command_success(Value::from(items).to_string(), &result))
+    }
+
+    /// Archive one Project (v2) item or every item with a status
+    #[tool(description = "Archive a Project (v2) item by ID, or every item whose Status equals status (e.g. Done), reporting the outcome per item. Archived items can be restored. Requires the project token scope")]
+    async fn project_archive_item(
+        &self,
+        #[tool(aggr)] param: ProjectArchiveItemParam,
+    ) -> Result<CallToolResult, McpError> {
+        let owner = param.owner.unwrap_or_else(|| "@me".to_string());
+        let items = self
+            .select_project_items(&owner, param.number, param.item_id, param.status.as_deref())
+            .await?;
+        let outcomes = self.apply_to_project_items(&owner, param.number, &items, "item-archive", "archived").await;
+        Ok(CallToolResult::success(vec![Content::text(Value::from(outcomes).to_string())]))
+    }
+
+    /// Remove one Project (v2) item or every item with a status
+    #[tool(description = "Remove a Project (v2) item by ID, or every item whose Status equals status, reporting the outcome per item. Issues and PRs stay in their repository but draft issues are deleted for good. Requires confirm set to true; without it the error lists the affected items. Requires the project token scope")]
+    async fn project_remove_item(
+        &self,
+        #[tool(aggr)] param: ProjectRemoveItemParam,
+    ) -> Result<CallToolResult, McpError> {
+        let owner = param.owner.unwrap_or_else(|| "@me".to_string());
+        let items = self
+            .select_project_items(&owner, param.number, param.item_id, param.status.as_deref())
+            .await?;
+        if !param.confirm {
+            let affected: Vec<Value> = items
+                .iter()
+                .map(|item| json!({"id": item["id"], "title": item["title"], "type": item["type"]}))
+                .collect();
+            return Err(McpError::invalid_params(
+                "Set confirm to true to remove these project items; draft issues cannot be recovered",
+                Some(json!({"items": affected})),
+            ));
+        }
+        let outcomes = self.apply_to_project_items(&owner, param.number, &items, "item-delete", "removed").await;
+        Ok(CallToolResult::success(vec![Content::text(Value::from(outcomes).to_string())]))
+    }
+
+    /// Create a Project (v2)
+    #[tool(description = "Create a Project (v2) for a user or organization, returning its number and URL. Requires the project token scope")]
+    async fn create_project(
+        &self,
+        #[tool(aggr)] param: CreateProjectParam,
+    ) -> Result<CallToolResult, McpError> {
+        let args = vec![
+            "project".to_string(),
+            "create".to_string(),
+            "--owner".to_string(),
+            project_owner(param.owner, param.is_org)?,
+            "--title".to_string(),
+            param.title,
+            "--format".to_string(),
+            "json".to_string(),
+        ];
+        let (project, result) = self.run_project_command(args, "Failed to create project").await?;
+        let summary = json!({"number": project["number"], "url": project["url"]});
+        Ok(command_success(summary.to_string(), &result))
+    }
+
+    /// Add a draft issue to a Project (v2)
+    #[tool(description = "Add a draft issue, not tied to any repository, to a Project (v2), returning the new item with its ID. Requires the project token scope")]
+    async fn project_add_draft(
+        &self,
+        #[tool(aggr)] param: ProjectAddDraftParam,
+    ) -> Result<CallToolResult, McpError> {
+        // gh project item-create has no --body-file, so the body goes on the command line
+        let mut args = vec![
+            "project".to_string(),
+            "item-create".to_string(),
+            param.number.to_string(),
+            "--owner".to_string(),
+            param.owner.unwrap_or_else(|| "@me".to_string()),
+            "--title".to_string(),
+            param.title,
+            "--format".to_string(),
+            "json".to_string(),
+        ];
+        if let Some(body) = param.body {
+            args.push("--body".to_string());
+            args.push(body);
+        }
+        let (item, result) = self.run_project_command(args, "Failed to add draft issue").await?;
+        Ok(command_success(item.to_string(), &result))
+    }
+
+    /// Add an issue or pull request to a Project (v2)
+    #[tool(description = "Add an issue or pull request to a Project (v2) by its URL, returning the new item with its ID. Requires the project token scope")]
+    async fn project_add_item(
+        &self,
+        #[tool(aggr)] param: ProjectAddItemParam,
+    ) -> Result<CallToolResult, McpError> {
+        let args = vec![
+            "project".to_string(),
+            "item-add".to_string(),
+            param.number.to_string(),
+            "--owner".to_string(),
+            param.owner.unwrap_or_else(|| "@me".to_string()),
+            "--url".to_string(),
+            param.url,
+            "--format".to_string(),
+            "json".to_string(),
+        ];
+        let (item, result) = self.run_project_command(args, "Failed to add project item").await?;
+        Ok(command_success(item.to_string(), &result))
+    }
+
+    /// List the fields of a Project (v2)
+    #[tool(description = "List fields of a Project (v2) with ID, name and type, the options of single-select fields and the iterations (with start date and duration in days) of iteration fields, plus the current_iteration of each iteration field. Requires the project token scope")]
+    async fn project_fields(
+        &self,
+        #[tool(aggr)] param: ProjectFieldsParam,
+    ) -> Result<CallToolResult, McpError> {
+        let args = vec![
+            "project".to_string(),
+            "field-list".to_string(),
+            param.number.to_string(),
+            "--owner".to_string(),
+            param.owner.unwrap_or_else(|| "@me".to_string()),
+            "--format".to_string(),
+            "json".to_string(),
+        ];
+        let (body, result) = self.run_project_command(args, "Failed to get project fields").await?;
+        
+        let today = Utc::now().date_naive();
+        let mut fields = Vec::new();
+        for field in body["fields"].as_array().cloned().unwrap_or_default() {
+            let mut shaped = json!({"id": field["id"], "name": field["name"], "type": field["type"]});
+            match field["type"].as_str().unwrap_or_default() {
+                "ProjectV2SingleSelectField" => shaped["options"] = field["options"].clone(),
+                "ProjectV2IterationField" => {
+                    let iterations = self.project_iterations(field["id"].as_str().unwrap_or_default()).await?;
+                    shaped["current_iteration"] = current_iteration(&iterations, today).cloned().unwrap_or(Value::Null);
+                    shaped["iterations"] = Value::from(iterations);
+                }
+                _ => {}
+            }
+            fields.push(shaped);
+        }
+        Ok(command_success(Value::from(fields).to_string(), &result))
+    }
+
+    /// Set a field of a Project (v2) item by field and option name
+    #[tool(description = "Set a field of a Project (v2) item by field name, e.g. Status to In Progress. Single-select options and iterations are matched by name; an unknown field or value returns the valid ones. Returns the updated item. Requires the project token scope")]
+    async fn project_set_field(
+        &self,
+        #[tool(aggr)] param: ProjectSetFieldParam,
+    ) -> Result<CallToolResult, McpError> {
+        let owner = param.owner.unwrap_or_else(|| "@me".to_string());
+        let project_args = |command: &str| {
+            vec![
+                "project".to_string(),
+                command.to_string(),
+                param.number.to_string(),
+                "--owner".to_string(),
+                owner.clone(),
+                "--format".to_string(),
+                "json".to_string(),
+            ]
+        };
+        
+        // gh project item-edit takes IDs, so resolve the project, field and option names first
+        let (project, _) = self.run_project_command(project_args("view"), "Failed to get project").await?;
+        let (fields, _) = self.run_project_command(project_args("field-list"), "Failed to get project fields").await?;
+        let fields = fields["fields"].as_array().cloned().unwrap_or_default();
+        let field = find_by_name(&fields, "name", &param.field, "field")?;
+        
+        let value_args = match field["type"].as_str().unwrap_or_default() {
+            "ProjectV2SingleSelectField" => {
+                let options = field["options"].as_array().cloned().unwrap_or_default();
+                let option = find_by_name(&options, "name", &param.value, "option")?;
+                vec!["--single-select-option-id".to_string(), option["id"].as_str().unwrap_or_default().to_string()]
+            }
+            "ProjectV2IterationField" => {
+                let iterations = self.project_iterations(field["id"].as_str().unwrap_or_default()).await?;
+                let iteration = find_by_name(&iterations, "title", &param.value, "iteration")?;
+                vec!["--iteration-id".to_string(), iteration["id"].as_str().unwrap_or_default().to_string()]
+            }
+            _ => vec![project_value_flag(&param.value).to_string(), param.value.clone()],
+        };
+        
+        let mut args = vec![
+            "project".to_string(),
+            "item-edit".to_string(),
+            "--id".to_string(),
+            param.item_id,
+            "--project-id".to_string(),
+            project["id"].as_str().unwrap_or_default().to_string(),
+            "--field-id".to_string(),
+            field["id"].as_str().unwrap_or_default().to_string(),
+            "--format".to_string(),
+            "json".to_string(),
+        ];
+        args.extend(value_args);
+        let (item, result) = self.run_project_command(args, "Failed to set project field").await?;
+        Ok(command_success(item.to_string(), &result))
+    }
+
+    /// List codespaces
+    #[tool(description = "List codespaces of the current user with repository, state, git status, machine and last used time, optionally for one repository")]
+    async fn list_codespaces(
+        &self,
+        #[tool(aggr)] param: ListCodespacesParam,
+    ) -> Result<CallToolResult, McpError> {
+        let (codespaces, result) = self.fetch_codespaces(param.repo.as_deref()).await?;
+        Ok(command_success(Value::from(codespaces).to_string(), &result))
+    }
+
+    /// Create a codespace and wait for it to become available
+    #[tool(description = "Create a codespace for a repository and optional branch, with optional machine type, devcontainer path, idle timeout and retention. Waits up to 5 minutes for it to become available and returns its name, web URL and state")]
+    async fn create_codespace(
+        &self,
+        #[tool(aggr)] param: CreateCodespaceParam,
+    ) -> Result<CallToolResult, McpError> {
+        let repo = format!("{}/{}", param.owner, param.repo);
+        if let Some(machine) = &param.machine {
+            self.ensure_codespace_machine(&repo, param.branch.as_deref(), machine).await?;
+        }
+        
+        let mut args = vec!["codespace".to_string(), "create".to_string(), "--repo".to_string(), repo];
+        if let Some(branch) = param.branch {
+            args.push("--branch".to_string());
+            args.push(branch);
+        }
+        if let Some(machine) = param.machine {
+            args.push("--machine".to_string());
+            args.push(machine);
+        }
+        if let Some(path) = param.devcontainer_path {
+            args.push("--devcontainer-path".to_string());
+            args.push(path);
+        }
+        if let Some(minutes) = param.idle_timeout_minutes {
+            args.push("--idle-timeout".to_string());
+            args.push(format!("{}m", minutes));
+        }
+        if let Some(days) = param.retention_days {
+            args.push("--retention-period".to_string());
+            args.push(format!("{}h", days * 24));
+        }
+        let result = self.run_gh_command_with_timeout(args, CommandTimeout::Long).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        drop(last_result);
+        
+        if !result.success {
+            return Err(command_error("Failed to create codespace", &result));
+        }
+        
+        // gh prints the new codespace name as the last line of stdout
+        let name = result.output.lines().last().unwrap_or_default().trim().to_string();
+        let codespace = self.wait_for_codespace(&name).await?;
+        let summary = json!({
+            "name": name,
+            "web_url": codespace["web_url"],
+            "state": codespace["state"],
+        });
+        Ok(command_success(summary.to_string(), &result))
+    }
+
+    /// Run a command inside a codespace
+    #[tool(description = "Run a shell command inside a codespace over SSH and return its exit code, stdout and stderr (each capped at 64K characters). Disabled unless the server sets GH_MCP_ALLOW_CODESPACE_EXEC")]
+    async fn codespace_exec(
+        &self,
+        #[tool(aggr)] param: CodespaceExecParam,
+    ) -> Result<CallToolResult, McpError> {
+        if !self.allow_codespace_exec {
+            return Err(McpError::invalid_request(
+                "codespace_exec is disabled; set GH_MCP_ALLOW_CODESPACE_EXEC=true on the server to enable it",
+                None,
+            ));
+        }
+        let timeout_secs = param
+            .timeout_secs
+            .unwrap_or(CODESPACE_EXEC_DEFAULT_TIMEOUT_SECS)
+            .clamp(1, CODESPACE_EXEC_MAX_TIMEOUT_SECS);
+        // Everything after "--" goes to the remote shell as one argument, so it is not re-split here
+        let args = vec![
+            "codespace".to_string(),
+            "ssh".to_string(),
+            "-c".to_string(),
+            param.name.clone(),
+            "--".to_string(),
+            param.command,
+        ];
+        let timeout = CommandTimeout::Custom(Duration::from_secs(timeout_secs));
+        let result = self.run_gh_command_with_timeout(args, timeout).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        // A non-zero exit is a result of the remote command; only a kill or spawn failure is an error
+        if result.exit_code == -1 {
+            return Err(command_error("Failed to run command in codespace", &result));
+        }
+        let stderr = result.stderr.as_deref().unwrap_or_default();
+        let truncated = result.output.chars().count() > CODESPACE_EXEC_MAX_OUTPUT
+            || stderr.chars().count() > CODESPACE_EXEC_MAX_OUTPUT;
+        let summary = json!({
+            "codespace": param.name,
+            "exit_code": result.exit_code,
+            "stdout": truncate_fragment(&result.output, CODESPACE_EXEC_MAX_OUTPUT),
+            "stderr": truncate_fragment(stderr, CODESPACE_EXEC_MAX_OUTPUT),
+            "truncated": truncated,
+        });
+        Ok(CallToolResult::success(vec![Content::text(summary.to_string())]))
+    }
+
+    /// List forwarded ports of a codespace, optionally changing one's visibility
+    #[tool(description = "List forwarded ports of a codespace with their label, visibility and browse URL (e.g. a dev server preview URL). Set port and visibility (private, org or public) to change who can reach a port; making a port public requires confirm set to true")]
+    async fn codespace_ports(
+        &self,
+        #[tool(aggr)] param: CodespacePortsParam,
+    ) -> Result<CallToolResult, McpError> {
+        match (param.port, param.visibility) {
+            (Some(port), Some(visibility)) => {
+                if visibility == PortVisibility::Public {
+                    require_confirm(param.confirm, "make the port public")?;
+                }
+                let args = vec![
+                    "codespace".to_string(),
+                    "ports".to_string(),
+                    "visibility".to_string(),
+                    format!("{}:{}", port, visibility.as_str()),
+                    "--codespace".to_string(),
+                    param.name.clone(),
+                ];
+                let result = self.run_gh_command(args).await;
+                if !result.success {
+                    let mut last_result = self.last_result.lock().await;
+                    *last_result = Some(result.clone());
+                    return Err(command_error("Failed to change port visibility", &result));
+                }
+            }
+            (None, None) => {}
+            _ => {
+                return Err(McpError::invalid_params(
+                    "port and visibility must be set together",
+                    None,
+                ));
+            }
+        }
+        
+        let args = vec![
+            "codespace".to_string(),
+            "ports".to_string(),
+            "--codespace".to_string(),
+            param.name,
+            "--json".to_string(),
+            "label,sourcePort,visibility,browseUrl".to_string(),
+        ];
+        let result = self.run_gh_read(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if result.success {
+            Ok(command_success(result.output.clone(), &result))
+        } else {
+            Err(command_error("Failed to get codespace ports", &result))
+        }
+    }
+
+    /// Get the creation logs of a codespace
+    #[tool(description = "Get the end of a codespace's creation log, tail_kb kilobytes (default 64), for debugging devcontainer setup")]
+    async fn codespace_logs(
+        &self,
+        #[tool(aggr)] param: CodespaceLogsParam,
+    ) -> Result<CallToolResult, McpError> {
+        let args = vec![
+            "codespace".to_string(),
+            "logs".to_string(),
+            "--codespace".to_string(),
+            param.name,
+        ];
+        let result = self.run_gh_read(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if !result.success {
+            return Err(command_error("Failed to get codespace logs", &result));
+        }
+        let max = param.tail_kb.unwrap_or(CODESPACE_LOG_DEFAULT_TAIL_KB) as usize * 1024;
+        Ok(command_success(tail_bytes(&result.output, max).to_string(), &result))
+    }
+
+    /// Stop a codespace
+    #[tool(description = "Stop a running codespace by name")]
+    async fn stop_codespace(
+        &self,
+        #[tool(aggr)] param: CodespaceParam,
+    ) -> Result<CallToolResult, McpError> {
+        let args = vec![
+            "codespace".to_string(),
+            "stop".to_string(),
+            "--codespace".to_string(),
+            param.name.clone(),
+        ];
+        let result = self.run_gh_command(args).await;
+        
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+        
+        if result.success {
+            Ok(command_success(format!("Stopped codespace {}", param.name), &result))
+        } else {
+            Err(command_error("Failed to stop codespace", &result))
+        }
+    }
+
+    /// Delete one codespace or bulk-delete idle ones
+    #[tool(description = "Delete a codespace by name, or set all with days to delete every codespace unused for at least that many days (optionally for one repo), reporting deleted, skipped (still provisioning) or failed per codespace. Unpushed changes are lost. Requires confirm set to true")]
+    async fn delete_codespace(
+        &self,
+        #[tool(aggr)] param: DeleteCodespaceParam,
+    ) -> Result<CallToolResult, McpError> {
+        require_confirm(param.confirm, "delete codespaces")?;
+        let days = match (&param.name, param.all, param.days) {
+            (Some(_), false, _) => None,
+            (None, true, Some(days)) => Some(days),
+            (None, true, None) => {
+                return Err(McpError::invalid_params("days is required when all is set", None));
+            }
+            _ => {
+                return Err(McpError::invalid_params("Set exactly one of name or all", None));
+            }
+        };
+        
+        let Some(days) = days else {
+            let name = param.name.unwrap_or_default();
+            let result = self.delete_codespace_named(&name).await;
+            
+            let mut last_result = self.last_result.lock().await;
+            *last_result = Some(result.clone());
+            
+            return if result.success {
+                Ok(command_success(format!("Deleted codespace {}", name), &result))
+            } else {
+                Err(command_error("Failed to delete codespace", &result))
+            };
+        };
+        
+        let (codespaces, _) = self.fetch_codespaces(param.repo.as_deref()).await?;
+        let now = Utc::now();
+        let mut outcomes = Vec::new();
+        for codespace in codespaces {
+            let idle_days = codespace_idle_days(&codespace, now);
+            if idle_days.is_none_or(|idle| idle < i64::from(days)) {
+                continue;
+            }
+            let name = codespace["name"].as_str().unwrap_or_default().to_string();
+            let state = codespace["state"].as_str().unwrap_or_default();
+            if CODESPACE_PROVISIONING_STATES.contains(&state) {
+                outcomes.push(json!({"name": name, "idle_days": idle_days, "status": "skipped", "reason": format!("codespace is {}", state)}));
+                continue;
+            }
+            let result = self.delete_codespace_named(&name).await;
+            if result.success {
+                outcomes.push(json!({"name": name, "idle_days": idle_days, "status": "deleted"}));
+            } else {
+                outcomes.push(json!({"name": name, "idle_days": idle_days, "status": "failed", "error": result.error}));
+            }
+            
+            let mut last_result = self.last_result.lock().await;
+            *last_result = Some(result);
+        }
+        
+        let summary = json!({"days": days, "codespaces": outcomes});
+        Ok(CallToolResult::success(vec![Content::text(summary.to_string())]))
+    }
+}
+
+impl GitHubService {
+    /// Stop accepting tool calls and wait up to `grace` for running ones to finish.
+    /// Returns a guard that keeps new calls blocked, or None if the grace period ran out
+    pub async fn drain(&self, grace: Duration) -> Option<OwnedRwLockWriteGuard<()>> {
+        tokio::time::timeout(grace, self.in_flight.clone().write_owned()).await.ok()
+    }
+
+    /// Run GitHub CLI command once and return result. Writes must not be retried:
+    /// a 502 after GitHub already applied one would create a duplicate issue, PR or comment
+    async fn run_gh_command(&self, args: Vec<String>) -> CommandResult {
+        self.execute_gh_command(args, None, CommandTimeout::Default).await
+    }
+
+    /// Run a read-only GitHub CLI command, retrying transient failures
+    async fn run_gh_read(&self, args: Vec<String>) -> CommandResult {
+        self.run_gh_read_with_timeout(args, CommandTimeout::Default).await
+    }
+
+    /// Run a read-only GitHub CLI command with a timeout per attempt, retrying transient failures
+    async fn run_gh_read_with_timeout(&self, args: Vec<String>, timeout: CommandTimeout) -> CommandResult {
+        self.retry_gh_command(args, timeout, self.retry_attempts, self.retry_base_delay).await
+    }
+
+    /// Run GitHub CLI command, retrying transient failures with exponential backoff
+    async fn retry_gh_command(
+        &self,
+        args: Vec<String>,
+        timeout: CommandTimeout,
+        max_attempts: u32,
+        base_delay: Duration,
+    ) -> CommandResult {
+        let max_attempts = max_attempts.max(1);
+        let mut attempt = 1;
+        loop {
+            let result = self.execute_gh_command(args.clone(), None, timeout).await;
+            if attempt >= max_attempts || !is_retryable(&result) {
+                return result;
+            }
+            let delay = base_delay.saturating_mul(1 << (attempt - 1).min(16));
+            tracing::warn!(
+                attempt,
+                max_attempts,
+                delay_ms = delay.as_millis() as u64,
+                "Transient gh failure, retrying"
+            );
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    /// Run GitHub CLI command, killing it once the timeout elapses
+    async fn run_gh_command_with_timeout(&self, args: Vec<String>, timeout: CommandTimeout) -> CommandResult {
+        self.execute_gh_command(args, None, timeout).await
+    }
+
+    /// Run GitHub CLI command with data written to its stdin, keeping it out of argv
+    async fn run_gh_command_with_input(&self, args: Vec<String>, input: String) -> CommandResult {
+        self.execute_gh_command(args, Some(input), CommandTimeout::Default).await
+    }
+
+    /// Spawn gh, optionally feed stdin, and collect its output within the timeout
+    async fn execute_gh_command(
+        &self,
+        args: Vec<String>,
+        input: Option<String>,
+        timeout: CommandTimeout,
+    ) -> CommandResult {
+        let started = Instant::now();
+        let result = if self.dry_run {
+            tracing::info!(args = ?args, "Dry run, not executing: gh {}", args.join(" "));
+            CommandResult {
+                success: true,
+                exit_code: 0,
+                output: "<dry-run>".to_string(),
+                error: None,
+                stderr: None,
+            }
+        } else {
+            // Wait for a free slot rather than failing; the timeout only covers the run itself
+            let _permit = self.gh_permits.acquire().await;
+            self.spawn_gh_command(&args, input, timeout).await
+        };
+        if args.first().is_some_and(|command| command == "api") && args.iter().any(|arg| arg == "-i" || arg == "--include") {
+            self.observe_rate_limit(&result.output).await;
+        }
+        if let Some(audit) = &self.audit {
+            audit.record(&args, result.success, result.exit_code, started.elapsed());
+        }
+        result
+    }
+
+    /// Spawn gh and wait for it to finish or time out
+    async fn spawn_gh_command(
+        &self,
+        args: &[String],
+        input: Option<String>,
+        timeout: CommandTimeout,
+    ) -> CommandResult {
+        tracing::info!(args = ?args, "Running gh command");
+        let output = async {
+            let mut child = Command::new(&*self.gh_binary)
+                .args(args)
+                .stdin(if input.is_some() { Stdio::piped() } else { Stdio::null() })
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .kill_on_drop(true)
+                .spawn()?;
+            if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+                stdin.write_all(input.as_bytes()).await?;
+            }
+            child.wait_with_output().await
+        };
+
+        let result = match tokio::time::timeout(timeout.duration(), output).await {
+            Ok(Ok(output)) => {
+                let success = output.status.success();
+                let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+                let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+
+                CommandResult {
+                    success,
+                    exit_code: output.status.code().unwrap_or(-1),
+                    output: stdout,
+                    error: if !success { Some(stderr.clone()) } else { None },
+                    stderr: Some(stderr),
+                }
+            },
+            Ok(Err(e)) => CommandResult {
+                success: false,
+                exit_code: -1,
+                output: String::new(),
+                error: Some(format!("Failed to execute command: {}", e)),
+                stderr: None,
+            },
+            Err(_) => CommandResult {
+                success: false,
+                exit_code: -1,
+                output: String::new(),
+                error: Some(format!("Command timed out after {} seconds", timeout.duration().as_secs())),
+                stderr: None,
+            },
+        };
+        tracing::info!(success = result.success, exit_code = result.exit_code, "gh command finished");
+        result
+    }
+
+    /// Determine the repository gh targets from the working directory
+    async fn resolve_current_repo(&self) -> Result<String, McpError> {
+        let args = vec![
+            "repo".to_string(),
+            "view".to_string(),
+            "--json".to_string(),
+            "nameWithOwner".to_string(),
+            "--jq".to_string(),
+            ".nameWithOwner".to_string(),
+        ];
+        let result = self.run_gh_read(args).await;
+        if !result.success {
+            return Err(command_error(
+                "Failed to determine current repository, pass repo explicitly",
+                &result,
+            ));
+        }
+        Ok(result.output.trim().to_string())
+    }
+
+    /// Fetch all labels of a repository
+    async fn fetch_labels(&self, repo: &str) -> Result<Vec<Label>, McpError> {
+        let args = vec![
+            "label".to_string(),
+            "list".to_string(),
+            "--repo".to_string(),
+            repo.to_string(),
+            "--json".to_string(),
+            "name,color,description".to_string(),
+            "--limit".to_string(),
+            "1000".to_string(),
+        ];
+        let result = self.run_gh_read(args).await;
+        if !result.success {
+            return Err(command_error("Failed to get labels list", &result));
+        }
+        serde_json::from_str(&result.output).map_err(|e| {
+            McpError::internal_error(
+                "Failed to parse labels list",
+                Some(json!({"error": e.to_string()})),
+            )
+        })
+    }
+
+    /// Fail with the list of available environments when `env` does not exist in the repository
+    async fn ensure_environment_exists(&self, owner: &str, repo: &str, env: &str) -> Result<(), McpError> {
+        let args = vec![
+            "api".to_string(),
+            format!("repos/{}/{}/environments", owner, repo),
+            "--jq".to_string(),
+            "[.environments[].name]".to_string(),
+        ];
+        let result = self.run_gh_read(args).await;
+        if !result.success {
+            return Err(command_error("Failed to get environments list", &result));
+        }
+        let available: Vec<String> = serde_json::from_str(result.output.trim()).unwrap_or_default();
+        if available.iter().any(|name| name.eq_ignore_ascii_case(env)) {
+            return Ok(());
+        }
+        Err(McpError::invalid_params(
+            format!("environment {} does not exist, available: [{}]", env, available.join(", ")),
+            Some(json!({"env": env, "available": available})),
+        ))
+    }
+
+    /// Fetch one kind of repository traffic (views or clones)
+    async fn traffic(&self, param: &RepoParam, kind: &str) -> Result<(Value, CommandResult), McpError> {
+        let args = vec![
+            "api".to_string(),
+            format!("repos/{}/{}/traffic/{}", param.owner, param.repo, kind),
+        ];
+        let result = self.run_gh_read(args).await;
+
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+
+        if !result.success {
+            if is_forbidden(&result) {
+                return Err(McpError::invalid_request(
+                    format!("Traffic data requires push access to the repo {}/{}", param.owner, param.repo),
+                    Some(command_error_data(&result)),
+                ));
+            }
+            return Err(command_error("Failed to get repository traffic", &result));
+        }
+
+        let body = parse_json_output(&result.output)?;
+        let traffic = json!({
+            "count": body["count"],
+            "uniques": body["uniques"],
+            "daily": body[kind],
+        });
+        Ok((traffic, result))
+    }
+
+    /// Check that every named repository exists in the organization, reporting all that do not
+    async fn ensure_org_repos_exist(&self, org: &str, repos: &[String]) -> Result<Vec<String>, McpError> {
+        let mut names = Vec::new();
+        let mut invalid = Vec::new();
+        for repo in repos {
+            // Accept both "repo" and "org/repo"
+            let name = match repo.split_once('/') {
+                Some((owner, name)) if owner.eq_ignore_ascii_case(org) => name,
+                Some(_) => {
+                    invalid.push(repo.clone());
+                    continue;
+                }
+                None => repo.as_str(),
+            };
+            let args = vec!["api".to_string(), format!("repos/{}/{}", org, name), "--jq".to_string(), ".name".to_string()];
+            if self.run_gh_read(args).await.success {
+                names.push(name.to_string());
+            } else {
+                invalid.push(repo.clone());
+            }
+        }
+        if invalid.is_empty() {
+            return Ok(names);
+        }
+        Err(McpError::invalid_params(
+            format!("Repositories not found in organization {}: {}", org, invalid.join(", ")),
+            Some(json!({"org": org, "invalid_repos": invalid})),
+        ))
+    }
+
+    /// Run a `gh project` command with JSON output, reporting a missing project scope clearly
+    async fn run_project_command(
+        &self,
+        args: Vec<String>,
+        failure_message: &'static str,
+    ) -> Result<(Value, CommandResult), McpError> {
+        let result = self.run_gh_command(args).await;
+
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+
+        if !result.success {
+            return Err(project_command_error(failure_message, &result));
+        }
+        Ok((parse_json_output(&result.output)?, result))
+    }
+
+    /// List the active and completed iterations of a project iteration field
+    async fn project_iterations(&self, field_id: &str) -> Result<Vec<Value>, McpError> {
+        let args = vec![
+            "api".to_string(),
+            "graphql".to_string(),
+            "-f".to_string(),
+            format!("query={}", PROJECT_ITERATIONS_QUERY),
+            "-f".to_string(),
+            format!("id={}", field_id),
+        ];
+        let result = self.run_gh_read(args).await;
+        if !result.success {
+            return Err(project_command_error("Failed to get project iterations", &result));
+        }
+        let body = parse_json_output(&result.output)?;
+        let configuration = &body["data"]["node"]["configuration"];
+        let iterations = ["iterations", "completedIterations"]
+            .iter()
+            .flat_map(|key| {
+                let completed = *key == "completedIterations";
+                configuration[key].as_array().cloned().unwrap_or_default().into_iter().map(move |mut iteration| {
+                    iteration["completed"] = json!(completed);
+                    iteration
+                })
+            })
+            .collect();
+        Ok(iterations)
+    }
+
+    /// List a project's items as flat rows, optionally only those with a status
+    async fn fetch_project_items(
+        &self,
+        owner: &str,
+        number: u64,
+        limit: u32,
+        status: Option<&str>,
+    ) -> Result<(Vec<Value>, CommandResult), McpError> {
+        let args = vec![
+            "project".to_string(),
+            "item-list".to_string(),
+            number.to_string(),
+            "--owner".to_string(),
+            owner.to_string(),
+            "--limit".to_string(),
+            limit.to_string(),
+            "--format".to_string(),
+            "json".to_string(),
+        ];
+        let (body, result) = self.run_project_command(args, "Failed to get project items").await?;
+        let items: Vec<Value> = body["items"]
+            .as_array()
+            .map(|items| items.iter().map(shape_project_item).collect())
+            .unwrap_or_default();
+        let items = match status {
+            Some(status) => items
+                .into_iter()
+                .filter(|item| {
+                    item["fields"]["status"]
+                        .as_str()
+                        .is_some_and(|value| value.eq_ignore_ascii_case(status))
+                })
+                .collect(),
+            None => items,
+        };
+        Ok((items, result))
+    }
+
+    /// Resolve the project items selected by exactly one of an item ID or a status
+    async fn select_project_items(
+        &self,
+        owner: &str,
+        number: u64,
+        item_id: Option<String>,
+        status: Option<&str>,
+    ) -> Result<Vec<Value>, McpError> {
+        match (item_id, status) {
+            (Some(item_id), None) => {
+                let (items, _) = self.fetch_project_items(owner, number, PROJECT_ITEM_SCAN_LIMIT, None).await?;
+                // Keep going with just the ID when the item is not listed, e.g. already archived
+                let item = items
+                    .into_iter()
+                    .find(|item| item["id"] == item_id.as_str())
+                    .unwrap_or_else(|| json!({"id": item_id}));
+                Ok(vec![item])
+            }
+            (None, Some(status)) => {
+                let (items, _) = self.fetch_project_items(owner, number, PROJECT_ITEM_SCAN_LIMIT, Some(status)).await?;
+                Ok(items)
+            }
+            _ => Err(McpError::invalid_params("Set exactly one of item_id or status", None)),
+        }
+    }
+
+    /// Run a `gh project` item command on each item, reporting the outcome per item
+    async fn apply_to_project_items(
+        &self,
+        owner: &str,
+        number: u64,
+        items: &[Value],
+        command: &str,
+        done: &str,
+    ) -> Vec<Value> {
+        let mut outcomes = Vec::new();
+        for item in items {
+            let args = vec![
+                "project".to_string(),
+                command.to_string(),
+                number.to_string(),
+                "--owner".to_string(),
+                owner.to_string(),
+                "--id".to_string(),
+                item["id"].as_str().unwrap_or_default().to_string(),
+            ];
+            let result = self.run_gh_command(args).await;
+            let outcome = if result.success {
+                json!({"id": item["id"], "title": item["title"], "status": done})
+            } else {
+                json!({"id": item["id"], "title": item["title"], "status": "failed", "error": result.error})
+            };
+            outcomes.push(outcome);
+
+            let mut last_result = self.last_result.lock().await;
+            *last_result = Some(result);
+        }
+        outcomes
+    }
+
+    /// Run an issue GraphQL mutation taking the issue node ID as `$id`
+    async fn issue_mutation(
+        &self,
+        param: &PinIssueParam,
+        mutation: &str,
+        failure_message: &'static str,
+    ) -> Result<CommandResult, McpError> {
+        // The REST API exposes the node ID GraphQL needs
+        let args = vec![
+            "api".to_string(),
+            format!("repos/{}/{}/issues/{}", param.owner, param.repo, param.number),
+            "--jq".to_string(),
+            ".node_id".to_string(),
+        ];
+        let result = self.run_gh_read(args).await;
+        if !result.success {
+            let mut last_result = self.last_result.lock().await;
+            *last_result = Some(result.clone());
+            return Err(command_error("Failed to get issue", &result));
+        }
+
+        let args = vec![
+            "api".to_string(),
+            "graphql".to_string(),
+            "-f".to_string(),
+            format!("query={}", mutation),
+            "-f".to_string(),
+            format!("id={}", result.output.trim()),
+        ];
+        let result = self.run_gh_command(args).await;
+
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+
+        if result.success {
+            Ok(result)
+        } else {
+            Err(command_error(failure_message, &result))
+        }
+    }
+
+    /// Resolve a discussion category ID, name or slug, returning the repository and category node IDs
+    async fn discussion_category(&self, owner: &str, repo: &str, category: &str) -> Result<(String, String), McpError> {
+        let args = vec![
+            "api".to_string(),
+            "graphql".to_string(),
+            "-f".to_string(),
+            format!("query={}", DISCUSSION_CATEGORIES_QUERY),
+            "-f".to_string(),
+            format!("owner={}", owner),
+            "-f".to_string(),
+            format!("repo={}", repo),
+        ];
+        let result = self.run_gh_read(args).await;
+        if !result.success {
+            let mut last_result = self.last_result.lock().await;
+            *last_result = Some(result.clone());
+            return Err(command_error("Failed to get discussion categories", &result));
+        }
+        let body = parse_json_output(&result.output)?;
+        let repository = &body["data"]["repository"];
+        let categories = repository["discussionCategories"]["nodes"]
+            .as_array()
+            .cloned()
+            .unwrap_or_default();
+        let found = categories.iter().find(|candidate| {
+            candidate["id"].as_str() == Some(category)
+                || ["name", "slug"]
+                    .iter()
+                    .any(|key| candidate[key].as_str().is_some_and(|value| value.eq_ignore_ascii_case(category)))
+        });
+        match (repository["id"].as_str(), found.and_then(|category| category["id"].as_str())) {
+            (Some(repository_id), Some(id)) => Ok((repository_id.to_string(), id.to_string())),
+            _ => {
+                let available: Vec<&str> = categories.iter().filter_map(|c| c["name"].as_str()).collect();
+                Err(McpError::invalid_params(
+                    format!("Discussion category {} not found in {}/{}", category, owner, repo),
+                    Some(json!({"available": available})),
+                ))
+            }
+        }
+    }
+
+    /// Get the notes of the release for a tag, null when the tag has no release
+    async fn release_by_tag(&self, owner: &str, repo: &str, tag: &str) -> Result<Value, McpError> {
+        let args = vec!["api".to_string(), format!("repos/{}/{}/releases/tags/{}", owner, repo, tag)];
+        let result = self.run_gh_read(args).await;
+        if !result.success {
+            if classify_error(&result) == ErrorKind::NotFound {
+                return Ok(json!({"tag": tag, "name": null, "published_at": null, "notes": null, "url": null}));
             }
-        },
-        Err(e) => CommandResult {
-            success: false,
-            output: String::new(),
-            error: Some(format!("Failed to execute command: {}", e)),
-        },
+            let mut last_result = self.last_result.lock().await;
+            *last_result = Some(result.clone());
+            return Err(command_error("Failed to get release", &result));
+        }
+        let release = parse_json_output(&result.output)?;
+        Ok(json!({
+            "tag": tag,
+            "name": release["name"],
+            "published_at": release["published_at"],
+            "notes": release["body"],
+            "url": release["html_url"],
+        }))
     }
-}
 
-#[tool(tool_box)]
-impl GitHubService {
-    pub fn new() -> Self {
-        Self {
-            last_result: Arc::new(Mutex::new(None)),
+    /// Find the notification thread of an issue or pull request by its web URL
+    async fn notification_thread_for_url(&self, url: &str) -> Result<String, McpError> {
+        let url = url.split(['#', '?']).next().unwrap_or_default().trim_end_matches('/');
+        // https://<host>/<owner>/<repo>/(issues|pull)/<number>
+        let segments: Vec<&str> = url.split('/').collect();
+        let repo = match segments.as_slice() {
+            ["https:", "", _, owner, repo, "issues" | "pull", _] => format!("{}/{}", owner, repo),
+            _ => {
+                return Err(McpError::invalid_params(
+                    format!("Unsupported URL {}, expected an issue or pull request URL", url),
+                    None,
+                ))
+            }
+        };
+        let result = self.api_list_all(&format!("repos/{}/notifications", repo), vec![("all", "true".to_string())]).await;
+        if !result.success {
+            return Err(command_error("Failed to get notifications", &result));
         }
+        parse_json_lines(&result.output)?
+            .iter()
+            .find(|thread| thread["subject"]["url"].as_str().and_then(api_url_to_web).as_deref() == Some(url))
+            .and_then(|thread| thread["id"].as_str().map(str::to_string))
+            .ok_or_else(|| {
+                McpError::invalid_params(
+                    format!("No notification thread for {}; threads only exist for items you have been notified about", url),
+                    None,
+                )
+            })
     }
 
-    /// List repositories of current user
-    #[tool(description = "List repositories of current user")]
-    async fn list_repos(&self) -> Result<CallToolResult, McpError> {
-        let args = vec!["repo".to_string(), "list".to_string(), "--json".to_string(), "name,description,url".to_string()];
-        let result = run_gh_command(args);
+    /// Fetch every page of a list endpoint for gh_api, merging the pages into one items array
+    async fn gh_api_paginated(&self, param: &GhApiParam, mut args: Vec<String>) -> Result<CallToolResult, McpError> {
+        let max_items = param.max_items.unwrap_or(1000) as usize;
+        let per_page_set = param.path.contains("per_page=")
+            || param.query.contains_key("per_page")
+            || param.fields.contains_key("per_page");
+        if !per_page_set {
+            args.push("-F".to_string());
+            args.push("per_page=100".to_string());
+        }
+        let mut items = Vec::new();
+        let mut more = false;
+        let result = if self.gh_supports_slurp {
+            // --slurp wraps all pages in one array instead of printing concatenated documents
+            args.push("--paginate".to_string());
+            args.push("--slurp".to_string());
+            let result = self.run_gh_read(args).await;
+            if !result.success {
+                let mut last_result = self.last_result.lock().await;
+                *last_result = Some(result.clone());
+                return Err(command_error("Failed to call API", &result));
+            }
+            let pages = parse_json_output(&result.output)?.as_array().cloned().unwrap_or_default();
+            for page in pages {
+                items.extend(page_items(page).ok_or_else(not_a_list_error)?);
+            }
+            result
+        } else {
+            // Older gh: follow the Link headers one request at a time, which also stops at max_items
+            args.push("-i".to_string());
+            loop {
+                let result = self.run_gh_read(args).await;
+                if !result.success {
+                    let mut last_result = self.last_result.lock().await;
+                    *last_result = Some(result.clone());
+                    return Err(command_error("Failed to call API", &result));
+                }
+                let response = parse_api_response(&result.output);
+                items.extend(page_items(parse_json_output(&response.body)?).ok_or_else(not_a_list_error)?);
+                let next = response.headers.get("link").and_then(|link| next_page_url(link));
+                match next {
+                    Some(next) if items.len() < max_items => {
+                        args = vec!["api".to_string(), "-i".to_string(), next.to_string()];
+                        args.extend(gh_api_header_args(param)?);
+                    }
+                    _ => {
+                        more = next.is_some();
+                        break result;
+                    }
+                }
+            }
+        };
         
         let mut last_result = self.last_result.lock().await;
         *last_result = Some(result.clone());
         
-        if result.success {
-            Ok(CallToolResult::success(vec![Content::text(result.output)]))
-        } else {
-            Err(McpError::internal_error(
-                "Failed to get repository list",
-                Some(json!({"error": result.error.unwrap_or_default()})),
-            ))
+        let truncated = more || items.len() > max_items;
+        items.truncate(max_items);
+        let summary = json!({"count": items.len(), "truncated": truncated, "items": items});
+        Ok(command_success(summary.to_string(), &result))
+    }
+
+    /// Keep the quota reported by the headers of a `gh api -i` response
+    async fn observe_rate_limit(&self, output: &str) {
+        let response = parse_api_response(output);
+        let number = |name: &str| response.headers.get(name).and_then(|v| v.parse::<u64>().ok());
+        if number("x-ratelimit-remaining").is_none() {
+            return;
         }
+        let resource = response.headers.get("x-ratelimit-resource").cloned().unwrap_or_else(|| "core".to_string());
+        let entry = rate_limit_entry(
+            number("x-ratelimit-limit"),
+            number("x-ratelimit-remaining"),
+            number("x-ratelimit-used"),
+            number("x-ratelimit-reset"),
+        );
+        self.rate_limits.lock().await.insert(resource, entry);
     }
 
-    /// Get repository information
-    #[tool(description = "Get information of specified repository")]
-    async fn repo_view(
-        &self,
-        #[tool(aggr)] param: RepoParam,
-    ) -> Result<CallToolResult, McpError> {
-        let repo = format!("{}/{}", param.owner, param.repo);
-        let args = vec!["repo".to_string(), "view".to_string(), repo, "--json".to_string(), "name,description,url,stars,forks,watchers".to_string()];
-        let result = run_gh_command(args);
-        
+    /// Drop the cached profile and results when the active account may change,
+    /// since cache keys do not name the user they were fetched for. Account tools call it
+    /// both before and after the gh auth command, so a read racing with the command cannot
+    /// leave the previous account's data cached
+    async fn forget_current_user(&self) {
+        *self.current_user.lock().await = None;
+        self.invalidate_cache();
+    }
+
+    /// Fetch every item of a list endpoint across all pages
+    async fn api_list_all(&self, path: &str, query: Vec<(&str, String)>) -> CommandResult {
+        let mut args = vec![
+            "api".to_string(),
+            "--paginate".to_string(),
+            "--method".to_string(),
+            "GET".to_string(),
+            path.to_string(),
+        ];
+        for (name, value) in query {
+            args.push("-f".to_string());
+            args.push(format!("{}={}", name, value));
+        }
+        args.push("--jq".to_string());
+        args.push(".[]".to_string());
+        let result = self.run_gh_read(args).await;
+
         let mut last_result = self.last_result.lock().await;
         *last_result = Some(result.clone());
-        
-        if result.success {
-            Ok(CallToolResult::success(vec![Content::text(result.output)]))
-        } else {
-            Err(McpError::internal_error(
-                "Failed to get repository information",
-                Some(json!({"error": result.error.unwrap_or_default()})),
-            ))
+        result
+    }
+
+    /// Logins of the users a list endpoint returns with a role filter
+    async fn logins_with_role(&self, path: &str, role: &str, org: &str) -> Result<Vec<String>, McpError> {
+        let result = self.api_list_all(path, vec![("role", role.to_string())]).await;
+        if !result.success {
+            return Err(org_command_error("Failed to get member roles", org, &result));
         }
+        Ok(parse_json_lines(&result.output)?
+            .iter()
+            .filter_map(|user| user["login"].as_str().map(str::to_string))
+            .collect())
     }
 
-    /// List issues of specified repository
-    #[tool(description = "List issues of specified repository")]
-    async fn list_issues(
-        &self,
-        #[tool(aggr)] param: RepoParam,
-    ) -> Result<CallToolResult, McpError> {
-        let repo = format!("{}/{}", param.owner, param.repo);
-        let args = vec!["issue".to_string(), "list".to_string(), "--repo".to_string(), repo, "--json".to_string(), "number,title,state,url".to_string()];
-        let result = run_gh_command(args);
-        
+    /// List a repository directory through the contents API, empty when it does not exist
+    async fn list_contents(&self, repo: &str, path: &str) -> Result<Vec<Value>, McpError> {
+        let args = vec!["api".to_string(), format!("repos/{}/contents/{}", repo, path)];
+        let result = self.run_gh_read(args).await;
+
         let mut last_result = self.last_result.lock().await;
         *last_result = Some(result.clone());
-        
-        if result.success {
-            Ok(CallToolResult::success(vec![Content::text(result.output)]))
-        } else {
-            Err(McpError::internal_error(
-                "Failed to get issues list",
-                Some(json!({"error": result.error.unwrap_or_default()})),
-            ))
+        drop(last_result);
+
+        if !result.success {
+            if classify_error(&result) == ErrorKind::NotFound {
+                return Ok(Vec::new());
+            }
+            return Err(command_error("Failed to list repository contents", &result));
         }
+        // A path naming a file returns an object rather than an array
+        Ok(parse_json_output(&result.output)?.as_array().cloned().unwrap_or_default())
     }
 
-    /// Create issue
-    #[tool(description = "Create issue in specified repository")]
-    async fn create_issue(
-        &self,
-        #[tool(aggr)] param: CreateIssueParam,
-    ) -> Result<CallToolResult, McpError> {
-        let mut args = vec!["issue".to_string(), "create".to_string()];
-        
-        if let Some(repo) = param.repo {
+    /// List the user's codespaces, optionally limited to one repository
+    async fn fetch_codespaces(&self, repo: Option<&str>) -> Result<(Vec<Value>, CommandResult), McpError> {
+        let mut args = vec![
+            "codespace".to_string(),
+            "list".to_string(),
+            "--json".to_string(),
+            CODESPACE_FIELDS.to_string(),
+        ];
+        if let Some(repo) = repo {
             args.push("--repo".to_string());
-            args.push(repo);
-        }
-        
-        args.push("--title".to_string());
-        args.push(param.title);
-        
-        if let Some(body) = param.body {
-            args.push("--body".to_string());
-            args.push(body);
+            args.push(repo.to_string());
         }
-        
-        let result = run_gh_command(args);
-        
+        let result = self.run_gh_read(args).await;
+
         let mut last_result = self.last_result.lock().await;
         *last_result = Some(result.clone());
-        
-        if result.success {
-            Ok(CallToolResult::success(vec![Content::text(result.output)]))
-        } else {
-            Err(McpError::internal_error(
-                "Failed to create issue",
-                Some(json!({"error": result.error.unwrap_or_default()})),
-            ))
+        drop(last_result);
+
+        if !result.success {
+            return Err(command_error("Failed to get codespaces list", &result));
         }
+        let codespaces = parse_json_output(&result.output)?.as_array().cloned().unwrap_or_default();
+        Ok((codespaces, result))
     }
 
-    /// List pull requests of specified repository
-    #[tool(description = "List pull requests of specified repository")]
-    async fn list_prs(
+    /// Check a machine type is available for the repository, listing the valid types otherwise
+    async fn ensure_codespace_machine(
         &self,
-        #[tool(aggr)] param: RepoParam,
-    ) -> Result<CallToolResult, McpError> {
-        let repo = format!("{}/{}", param.owner, param.repo);
-        let args = vec!["pr".to_string(), "list".to_string(), "--repo".to_string(), repo, "--json".to_string(), "number,title,state,url".to_string()];
-        let result = run_gh_command(args);
-        
-        let mut last_result = self.last_result.lock().await;
-        *last_result = Some(result.clone());
-        
+        repo: &str,
+        branch: Option<&str>,
+        machine: &str,
+    ) -> Result<(), McpError> {
+        let mut args = vec![
+            "api".to_string(),
+            "--method".to_string(),
+            "GET".to_string(),
+            format!("repos/{}/codespaces/machines", repo),
+        ];
+        if let Some(branch) = branch {
+            args.push("-f".to_string());
+            args.push(format!("ref={}", branch));
+        }
+        let result = self.run_gh_read(args).await;
+        if !result.success {
+            return Err(command_error("Failed to get codespace machine types", &result));
+        }
+        let body = parse_json_output(&result.output)?;
+        let machines: Vec<Value> = body["machines"]
+            .as_array()
+            .map(|machines| {
+                machines
+                    .iter()
+                    .map(|m| json!({"name": m["name"], "display_name": m["display_name"]}))
+                    .collect()
+            })
+            .unwrap_or_default();
+        if machines.iter().any(|m| m["name"] == machine) {
+            return Ok(());
+        }
+        Err(McpError::invalid_params(
+            format!("Machine type {} is not available for {}", machine, repo),
+            Some(json!({"machine": machine, "available": machines})),
+        ))
+    }
+
+    /// Poll a codespace until it is available or the timeout elapses, returning its last known state
+    async fn wait_for_codespace(&self, name: &str) -> Result<Value, McpError> {
+        let deadline = Instant::now() + CODESPACE_READY_TIMEOUT;
+        loop {
+            let args = vec!["api".to_string(), format!("user/codespaces/{}", name)];
+            let result = self.run_gh_read(args).await;
+            if !result.success {
+                return Err(command_error("Failed to get codespace state", &result));
+            }
+            let codespace = parse_json_output(&result.output)?;
+            if codespace["state"] == "Available" || Instant::now() >= deadline {
+                return Ok(codespace);
+            }
+            tokio::time::sleep(CODESPACE_POLL_INTERVAL).await;
+        }
+    }
+
+    /// Delete one codespace, discarding unpushed changes
+    async fn delete_codespace_named(&self, name: &str) -> CommandResult {
+        let args = vec![
+            "codespace".to_string(),
+            "delete".to_string(),
+            "--codespace".to_string(),
+            name.to_string(),
+            "--force".to_string(),
+        ];
+        self.run_gh_command(args).await
+    }
+
+    /// Run a read-only command, serving repeated identical calls from the cache
+    async fn run_cached(&self, args: Vec<String>) -> CommandResult {
+        let Some(cache) = &self.cache else {
+            return self.run_gh_read(args).await;
+        };
+        let key = serde_json::to_string(&args).unwrap_or_default();
+        if let Some(result) = cache.get(&key) {
+            tracing::debug!(args = ?args, "Serving gh command from cache");
+            return result;
+        }
+        let result = self.run_gh_read(args).await;
         if result.success {
-            Ok(CallToolResult::success(vec![Content::text(result.output)]))
-        } else {
-            Err(McpError::internal_error(
-                "Failed to get pull requests list",
-                Some(json!({"error": result.error.unwrap_or_default()})),
-            ))
+            cache.insert(key, result.clone());
         }
+        result
     }
 
-    /// Create pull request
-    #[tool(description = "Create pull request")]
-    async fn create_pr(
+    /// Drop cached results after a command changed data they may include
+    fn invalidate_cache(&self) {
+        if let Some(cache) = &self.cache {
+            cache.clear();
+        }
+    }
+
+    /// Fetch one page of an API list endpoint, shaping each item and
+    /// reporting the next page number when the Link header has one
+    async fn api_page(
         &self,
-        #[tool(aggr)] param: CreatePRParam,
-    ) -> Result<CallToolResult, McpError> {
-        let mut args = vec!["pr".to_string(), "create".to_string()];
-        
-        if let Some(repo) = param.repo {
-            args.push("--repo".to_string());
-            args.push(repo);
+        path: &str,
+        query: Vec<(&str, String)>,
+        page: &PageParam,
+        shape: fn(&Value) -> Value,
+        failure_message: &'static str,
+    ) -> Result<(Value, CommandResult), McpError> {
+        let per_page = page.per_page.unwrap_or(30).clamp(1, 100);
+        let page_number = page.page.unwrap_or(1).max(1);
+        let mut args = vec![
+            "api".to_string(),
+            "-i".to_string(),
+            "--method".to_string(),
+            "GET".to_string(),
+            path.to_string(),
+            "-F".to_string(),
+            format!("per_page={}", per_page),
+            "-F".to_string(),
+            format!("page={}", page_number),
+        ];
+        for (name, value) in query {
+            args.push("-f".to_string());
+            args.push(format!("{}={}", name, value));
         }
-        
-        args.push("--title".to_string());
-        args.push(param.title);
-        
-        if let Some(body) = param.body {
-            args.push("--body".to_string());
-            args.push(body);
+        let result = self.run_gh_read(args).await;
+
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+
+        if !result.success {
+            return Err(command_error(failure_message, &result));
         }
-        
-        args.push("--base".to_string());
-        args.push(param.base);
-        
-        args.push("--head".to_string());
-        args.push(param.head);
-        
-        let result = run_gh_command(args);
-        
+
+        let response = parse_api_response(&result.output);
+        let body = parse_json_output(&response.body)?;
+        let items: Vec<Value> = body
+            .as_array()
+            .map(|items| items.iter().map(shape).collect())
+            .unwrap_or_default();
+        let next_page = response.headers.get("link").and_then(|link| next_page_from_link(link));
+
+        let summary = json!({
+            "items": items,
+            "page": page_number,
+            "per_page": per_page,
+            "next_page": next_page,
+        });
+        Ok((summary, result))
+    }
+
+    /// Run a repository search, returning the shaped results and the raw command result
+    async fn repo_search(
+        &self,
+        param: &SearchReposParam,
+    ) -> Result<(Value, CommandResult), McpError> {
+        let args = search_api_args("search/repositories", build_repo_query(param), param.limit);
+        let result = self.run_gh_read(args).await;
+
         let mut last_result = self.last_result.lock().await;
         *last_result = Some(result.clone());
-        
-        if result.success {
-            Ok(CallToolResult::success(vec![Content::text(result.output)]))
-        } else {
-            Err(McpError::internal_error(
-                "Failed to create pull request",
-                Some(json!({"error": result.error.unwrap_or_default()})),
-            ))
+
+        if !result.success {
+            return Err(command_error("Failed to search repositories", &result));
         }
+
+        let body = parse_json_output(&result.output)?;
+        let items: Vec<Value> = body["items"]
+            .as_array()
+            .map(|items| {
+                items
+                    .iter()
+                    .map(|item| {
+                        json!({
+                            "full_name": item["full_name"],
+                            "description": item["description"],
+                            "language": item["language"],
+                            "stars": item["stargazers_count"],
+                            "updated_at": item["updated_at"],
+                            "url": item["html_url"],
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let summary = json!({
+            "total_count": body["total_count"],
+            "incomplete_results": body["incomplete_results"],
+            "items": items,
+        });
+        Ok((summary, result))
     }
 
-    /// Clone repository
-    #[tool(description = "Clone GitHub repository")]
-    async fn clone_repo(
+    /// Run a code search, returning the shaped results and the raw command result
+    async fn code_search(
         &self,
-        #[tool(aggr)] param: CloneRepoParam,
-    ) -> Result<CallToolResult, McpError> {
-        let mut args = vec!["repo".to_string(), "clone".to_string(), param.repo];
-        
-        if let Some(dir) = param.directory {
-            args.push(dir);
+        param: &SearchCodeParam,
+    ) -> Result<(Value, CommandResult), McpError> {
+        // The REST endpoint is used so text matches and the remaining quota come back in a single request
+        let mut args = search_api_args("search/code", build_code_query(param), param.limit);
+        args.push("-i".to_string());
+        args.push("-H".to_string());
+        args.push("Accept: application/vnd.github.text-match+json".to_string());
+        let result = self.run_gh_read(args).await;
+
+        let mut last_result = self.last_result.lock().await;
+        *last_result = Some(result.clone());
+
+        let response = parse_api_response(&result.output);
+        if !result.success {
+            let mut data = command_error_data(&result);
+            data["rate_limit"] = rate_limit_from_headers(&response.headers);
+            return Err(McpError::internal_error("Failed to search code", Some(data)));
         }
-        
-        let result = run_gh_command(args);
-        
+
+        let body = parse_json_output(&response.body)?;
+        let items: Vec<Value> = body["items"]
+            .as_array()
+            .map(|items| {
+                items
+                    .iter()
+                    .map(|item| {
+                        let fragments: Vec<String> = item["text_matches"]
+                            .as_array()
+                            .map(|matches| {
+                                matches
+                                    .iter()
+                                    .filter_map(|m| m["fragment"].as_str())
+                                    .map(|f| truncate_fragment(f, MAX_FRAGMENT_LEN))
+                                    .collect()
+                            })
+                            .unwrap_or_default();
+                        json!({
+                            "repository": item["repository"]["full_name"],
+                            "path": item["path"],
+                            "url": item["html_url"],
+                            "fragments": fragments,
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let summary = json!({
+            "status": response.status,
+            "total_count": body["total_count"],
+            "incomplete_results": body["incomplete_results"],
+            "items": items,
+            "rate_limit": rate_limit_from_headers(&response.headers),
+        });
+        Ok((summary, result))
+    }
+
+
+    /// Run an issue or pull request search, returning the shaped results and the raw command result
+    async fn issue_search(
+        &self,
+        param: &SearchIssuesParam,
+        pull_requests: bool,
+    ) -> Result<(Value, CommandResult), McpError> {
+        let args = search_api_args("search/issues", build_issue_query(param, pull_requests), param.limit);
+        let result = self.run_gh_read(args).await;
+
         let mut last_result = self.last_result.lock().await;
         *last_result = Some(result.clone());
-        
-        if result.success {
-            Ok(CallToolResult::success(vec![Content::text(result.output)]))
-        } else {
-            Err(McpError::internal_error(
-                "Failed to clone repository",
-                Some(json!({"error": result.error.unwrap_or_default()})),
-            ))
+
+        if !result.success {
+            return Err(command_error("Failed to search issues", &result));
         }
+
+        let body = parse_json_output(&result.output)?;
+        let items: Vec<Value> = body["items"]
+            .as_array()
+            .map(|items| {
+                items
+                    .iter()
+                    .map(|item| {
+                        // Search results only link the repository through its API URL
+                        let repository = item["repository_url"]
+                            .as_str()
+                            .and_then(|url| url.split("/repos/").nth(1))
+                            .unwrap_or_default();
+                        json!({
+                            "repository": repository,
+                            "number": item["number"],
+                            "title": item["title"],
+                            "state": item["state"],
+                            "author": item["user"]["login"],
+                            "created_at": item["created_at"],
+                            "url": item["html_url"],
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let summary = json!({
+            "total_count": body["total_count"],
+            "incomplete_results": body["incomplete_results"],
+            "items": items,
+        });
+        Ok((summary, result))
     }
 
-    /// Run any GitHub CLI command
-    #[tool(description = "Run any GitHub CLI command")]
-    async fn run_command(
+    /// Run a commit search, returning the shaped results and the raw command result
+    async fn commit_search(
         &self,
-        #[tool(param)]
-        #[schemars(description = "GitHub CLI command without gh prefix")]
-        command: String,
-    ) -> Result<CallToolResult, McpError> {
-        let args: Vec<String> = command.split_whitespace().map(|s| s.to_string()).collect();
-        let result = run_gh_command(args);
-        
+        param: &SearchCommitsParam,
+    ) -> Result<(Value, CommandResult), McpError> {
+        let mut args = search_api_args("search/commits", build_commit_query(param), param.limit);
+        if let Some(sort) = param.sort {
+            let sort = match sort {
+                CommitSort::AuthorDate => "author-date",
+                CommitSort::CommitterDate => "committer-date",
+            };
+            args.push("-f".to_string());
+            args.push(format!("sort={}", sort));
+        }
+        let result = self.run_gh_read_with_timeout(args, CommandTimeout::Long).await;
+
         let mut last_result = self.last_result.lock().await;
         *last_result = Some(result.clone());
-        
-        if result.success {
-            Ok(CallToolResult::success(vec![Content::text(result.output)]))
-        } else {
-            Err(McpError::internal_error(
-                "Failed to execute command",
-                Some(json!({"error": result.error.unwrap_or_default()})),
-            ))
+
+        if !result.success {
+            return Err(command_error("Failed to search commits", &result));
         }
+
+        let body = parse_json_output(&result.output)?;
+        let items: Vec<Value> = body["items"]
+            .as_array()
+            .map(|items| {
+                items
+                    .iter()
+                    .map(|item| {
+                        let headline = item["commit"]["message"]
+                            .as_str()
+                            .and_then(|message| message.lines().next())
+                            .unwrap_or_default();
+                        let author = if item["author"]["login"].is_string() {
+                            &item["author"]["login"]
+                        } else {
+                            &item["commit"]["author"]["name"]
+                        };
+                        json!({
+                            "sha": item["sha"],
+                            "repository": item["repository"]["full_name"],
+                            "headline": headline,
+                            "author": author,
+                            "date": item["commit"]["author"]["date"],
+                            "url": item["html_url"],
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let summary = json!({
+            "total_count": body["total_count"],
+            "incomplete_results": body["incomplete_results"],
+            "items": items,
+        });
+        Ok((summary, result))
     }
 
-    /// Get GitHub authentication status
-    #[tool(description = "Check GitHub CLI authentication status")]
-    async fn auth_status(&self) -> Result<CallToolResult, McpError> {
-        let args = vec!["auth".to_string(), "status".to_string()];
-        let result = run_gh_command(args);
-        
+
+    /// Run a user search, returning the shaped results and the raw command result
+    async fn user_search(
+        &self,
+        param: &SearchUsersParam,
+    ) -> Result<(Value, CommandResult), McpError> {
+        let args = vec![
+            "api".to_string(),
+            "graphql".to_string(),
+            "-f".to_string(),
+            format!("query={}", SEARCH_USERS_QUERY),
+            "-f".to_string(),
+            format!("q={}", build_user_query(param)),
+            "-F".to_string(),
+            format!("first={}", param.limit.unwrap_or(30).min(100)),
+        ];
+        let result = self.run_gh_read(args).await;
+
         let mut last_result = self.last_result.lock().await;
         *last_result = Some(result.clone());
-        
-        Ok(CallToolResult::success(vec![Content::text(result.output)]))
+
+        if !result.success {
+            return Err(command_error("Failed to search users", &result));
+        }
+
+        let body = parse_json_output(&result.output)?;
+        let search = &body["data"]["search"];
+        let items: Vec<Value> = search["nodes"]
+            .as_array()
+            .map(|nodes| {
+                nodes
+                    .iter()
+                    .filter(|node| node["login"].is_string())
+                    .map(|node| {
+                        json!({
+                            "login": node["login"],
+                            "name": node["name"],
+                            "type": node["__typename"],
+                            "url": node["url"],
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        if items.is_empty() {
+            let summary = json!({
+                "total_count": 0,
+                "items": [],
+                "message": "No users matched the query",
+            });
+            return Ok((summary, result));
+        }
+
+        let summary = json!({
+            "total_count": search["userCount"],
+            "items": items,
+        });
+        Ok((summary, result))
     }
+
 }
 
-#[tool(tool_box)]
 impl ServerHandler for GitHubService {
     fn get_info(&self) -> ServerInfo {
         ServerInfo {
@@ -329,7 +8548,7 @@ impl ServerHandler for GitHubService {
                 .enable_tools()
                 .build(),
             server_info: Implementation::from_build_env(),
-            instructions: Some("This is a GitHub CLI wrapper server that provides GitHub operation tools. Please ensure GitHub CLI is installed and logged in before use. Use auth_status to check login status, list_repos to list repositories, repo_view to view repository information, list_issues and list_prs to view issues and PRs, create_issue and create_pr to create issues and PRs, clone_repo to clone repositories, and run_command to run any GitHub CLI command.".to_string()),
+            instructions: Some("This is a GitHub CLI wrapper server that provides GitHub operation tools. Please ensure GitHub CLI is installed and logged in before use. Use auth_status to check login status, rate_limit to check the remaining API quota before bulk operations, gh_api and graphql to call API endpoints without a dedicated tool, metrics for per-tool call counts and latencies, list_notifications, mark_notification_read, mark_thread_done and set_thread_subscription to see and clear what needs your attention, whoami to get your own login, auth_login, login_with_token and auth_logout to log in and out, switch_account to change the active account, token_scopes to check granted scopes, auth_token to get the token for direct API calls, gh_environment to see which gh executable, config directory and host are in use, list_repos to list repositories, star_repo, unstar_repo, is_starred and list_starred for stars, list_followers, list_following, is_following, follow_user and unfollow_user for follows, set_repo_subscription, get_repo_subscription and list_watched to manage notifications, repo_view to view repository information, repo_traffic to get view and clone counts, list_issues and list_prs to view issues and PRs, find_similar_issues to check for duplicates before filing, take_issue to assign an issue to yourself, transfer_issue to move an issue to another repository, lock_issue and unlock_issue to lock conversations, pin_issue and unpin_issue to pin issues, list_issue_timeline_events for an issue's full history, list_mergeable_prs to get the PRs ready to merge, list_commits, list_branches, list_contributors and pr_files to browse history, get_pr_diff to get a PR's unified diff, list_pr_reviews and dismiss_pr_review for a PR's reviews, list_pr_review_comments and reply_pr_review_comment for inline review comments (paginated with page and next_page), resolve_ref to turn a branch or tag into a SHA, create_issue and create_pr to create issues and PRs, list_templates and create_issue_from_template to discover and fill in issue templates, edit_issue_comment and delete_issue_comment to revise or remove a comment, clone_repo to clone repositories, list_labels, create_label, edit_label, delete_label and clone_labels to manage labels, bulk_label to relabel many issues at once, list_milestones, create_milestone, edit_milestone and close_milestone to manage milestones, list_repo_secrets, set_repo_secret and delete_repo_secret to manage secrets (app selects the actions, dependabot or codespaces store), list_org_secrets and set_org_secret for organization secrets, list_org_members, set_org_membership, list_teams, team_members, list_team_repos and set_team_repo_permission for organization membership, org_audit_log to query an organization's audit log, list_discussions, get_discussion, create_discussion and create_discussion_comment for GitHub Discussions, check_collaborator to verify a user's repository permission, list_deploy_keys and add_deploy_key for repository deploy keys, list_webhooks, create_webhook and delete_webhook for repository webhooks, release_diff to summarize what changed between two releases, generate_release_notes for GitHub's generated release notes, list_ssh_keys, add_ssh_key, delete_ssh_key, list_gpg_keys, add_gpg_key and delete_gpg_key for your account keys, list_environments, set_env_secret and delete_env_secret for deployment environments, list_variables, set_variable and delete_variable to manage Actions variables, list_projects, project_items and project_fields (with the current iteration) to browse Projects (v2), create_project to create one, project_add_draft to add draft issues, project_add_item and project_set_field to add items and set fields such as Status by name, project_archive_item and project_remove_item to clean up items one by one or by status, create_codespace, list_codespaces, stop_codespace and delete_codespace (with bulk cleanup of idle codespaces) to manage codespaces, codespace_exec to run a command in a codespace when enabled, codespace_ports to get preview URLs and change port visibility, codespace_logs to read creation logs, search to search any kind of entity (or search_repos, search_code, search_issues, search_prs, search_commits and search_users for a specific kind), user_view to view a user, run_command to run any GitHub CLI command, and get_last_result to inspect the most recent command result.".to_string()),
         }
     }
 
@@ -340,4 +8559,182 @@ impl ServerHandler for GitHubService {
     ) -> Result<InitializeResult, McpError> {
         Ok(self.get_info())
     }
+
+    async fn list_tools(
+        &self,
+        _request: PaginatedRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListToolsResult, McpError> {
+        Ok(ListToolsResult {
+            next_cursor: None,
+            tools: Self::tool_box().list(),
+        })
+    }
+
+    /// Dispatch a tool call inside a span carrying a unique request ID,
+    /// so every gh command it runs can be traced back to the call
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        let span = tracing::info_span!(
+            "tool_call",
+            request_id = %Uuid::new_v4(),
+            mcp_request_id = %context.id,
+            tool = %request.name,
+        );
+        async {
+            let _in_flight = self.in_flight.read().await;
+            tracing::info!("Handling tool call");
+            let started = Instant::now();
+            let tool = request.name.clone();
+            let context = ToolCallContext::new(self, request, context);
+            let result = Self::tool_box().call(context).await;
+            if let Err(e) = &result {
+                tracing::warn!(error = %e.message, "Tool call failed");
+            }
+            let success = matches!(&result, Ok(result) if result.is_error != Some(true));
+            self.metrics.record(&tool, success, started.elapsed());
+            result
+        }
+        .instrument(span)
+        .await
+    }
 } 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delete_secret_args_pass_app() {
+        let args = delete_secret_args("NPM_TOKEN", "octo/repo", Some(SecretApp::Dependabot));
+        assert!(args.windows(2).any(|pair| pair == ["--app", "dependabot"]));
+    }
+
+    #[test]
+    fn delete_secret_args_default_to_actions() {
+        let args = delete_secret_args("NPM_TOKEN", "octo/repo", None);
+        assert!(args.windows(2).any(|pair| pair == ["--app", "actions"]));
+    }
+
+    #[test]
+    fn next_page_url_follows_rel_next() {
+        let link = r#"<https://api.github.com/repositories/1/issues?page=2>; rel="next", <https://api.github.com/repositories/1/issues?page=5>; rel="last""#;
+        assert_eq!(next_page_url(link), Some("https://api.github.com/repositories/1/issues?page=2"));
+    }
+
+    #[test]
+    fn next_page_url_is_none_on_last_page() {
+        let link = r#"<https://api.github.com/repositories/1/issues?page=1>; rel="first", <https://api.github.com/repositories/1/issues?page=4>; rel="prev""#;
+        assert_eq!(next_page_url(link), None);
+    }
+
+    #[test]
+    fn page_items_unwraps_object_pages() {
+        let page = json!({"total_count": 2, "workflow_runs": [{"id": 1}, {"id": 2}]});
+        assert_eq!(page_items(page), Some(vec![json!({"id": 1}), json!({"id": 2})]));
+    }
+
+    #[test]
+    fn page_items_keeps_array_pages() {
+        let page = json!([{"id": 1}, {"id": 2}]);
+        assert_eq!(page_items(page), Some(vec![json!({"id": 1}), json!({"id": 2})]));
+    }
+
+    #[test]
+    fn parse_api_response_splits_headers_from_body() {
+        let output = "HTTP/2.0 200 OK\r\nContent-Type: application/json\r\nX-RateLimit-Remaining: 4999\r\n\r\n{\"login\":\"octocat\"}";
+        let response = parse_api_response(output);
+        assert_eq!(response.status, 200);
+        assert_eq!(response.headers.get("x-ratelimit-remaining").map(String::as_str), Some("4999"));
+        assert_eq!(response.body, r#"{"login":"octocat"}"#);
+    }
+
+    fn failed(stderr: &str) -> CommandResult {
+        CommandResult {
+            success: false,
+            exit_code: 1,
+            output: String::new(),
+            error: Some(stderr.to_string()),
+            stderr: Some(stderr.to_string()),
+        }
+    }
+
+    #[test]
+    fn is_retryable_only_for_transient_failures() {
+        let cases = [
+            ("HTTP 401: Bad credentials (https://api.github.com/user)", false),
+            ("HTTP 404: Not Found (https://api.github.com/repos/octo/missing)", false),
+            ("HTTP 422: Validation Failed (https://api.github.com/repos/octo/repo/issues)", false),
+            ("HTTP 502: Bad Gateway (https://api.github.com/repos/octo/repo)", true),
+            ("HTTP 429: Too Many Requests (https://api.github.com/search/code)", true),
+            ("read tcp 10.0.0.2:51234->140.82.112.6:443: read: connection reset by peer", true),
+        ];
+        for (stderr, retryable) in cases {
+            assert_eq!(is_retryable(&failed(stderr)), retryable, "{}", stderr);
+        }
+    }
+
+    #[test]
+    fn classify_error_kinds() {
+        let cases = [
+            ("HTTP 401: Bad credentials (https://api.github.com/user)", ErrorKind::Auth),
+            ("HTTP 404: Not Found (https://api.github.com/repos/octo/missing)", ErrorKind::NotFound),
+            ("HTTP 422: Validation Failed (https://api.github.com/repos/octo/repo/issues)", ErrorKind::Validation),
+            ("HTTP 429: Too Many Requests (https://api.github.com/search/code)", ErrorKind::RateLimited),
+            (
+                "HTTP 403: You have exceeded a secondary rate limit. Please wait a few minutes before you try again; if you are authenticated, check your authentication",
+                ErrorKind::RateLimited,
+            ),
+            ("HTTP 502: Bad Gateway (https://api.github.com/repos/octo/repo)", ErrorKind::Unknown),
+        ];
+        for (stderr, kind) in cases {
+            assert_eq!(classify_error(&failed(stderr)), kind, "{}", stderr);
+        }
+    }
+
+    #[test]
+    fn split_front_matter_reads_inline_values() {
+        let text = "---\nname: Bug report\ntitle: '[Bug] '\nlabels: bug, triage\n---\nSteps: {{steps}}\n";
+        let (front_matter, body) = split_front_matter(text);
+        assert_eq!(front_matter["title"], "[Bug] ");
+        assert_eq!(front_matter_list(&front_matter["labels"]), ["bug", "triage"]);
+        assert_eq!(body, "Steps: {{steps}}\n");
+    }
+
+    #[test]
+    fn split_front_matter_reads_block_lists() {
+        let text = "---\nname: Bug report\nlabels:\n  - bug\n  - \"needs triage\"\nassignees: ''\n---\nBody\n";
+        let (front_matter, body) = split_front_matter(text);
+        assert_eq!(front_matter_list(&front_matter["labels"]), ["bug", "needs triage"]);
+        assert_eq!(front_matter["assignees"], "");
+        assert_eq!(body, "Body\n");
+    }
+
+    #[test]
+    fn split_front_matter_without_front_matter_keeps_text() {
+        let (front_matter, body) = split_front_matter("Just a body\n");
+        assert!(front_matter.is_empty());
+        assert_eq!(body, "Just a body\n");
+    }
+
+    #[test]
+    fn render_template_fills_placeholders() {
+        let fields = HashMap::from([("version".to_string(), "1.2.0".to_string())]);
+        assert_eq!(render_template("Broken in {{ version }}", &fields), Ok("Broken in 1.2.0".to_string()));
+    }
+
+    #[test]
+    fn render_template_reports_missing_fields_once() {
+        let fields = HashMap::from([("version".to_string(), "1.2.0".to_string())]);
+        let rendered = render_template("{{os}} {{version}} {{os}} {{browser}}", &fields);
+        assert_eq!(rendered, Err(vec!["os".to_string(), "browser".to_string()]));
+    }
+
+    #[test]
+    fn render_template_keeps_unterminated_placeholder() {
+        assert_eq!(render_template("Hello {{name", &HashMap::new()), Ok("Hello {{name".to_string()));
+    }
+}
@@ -1,30 +1,99 @@
+mod audit;
+mod cache;
+mod config;
 mod github;
+mod metrics;
+mod redact;
 
 use anyhow::Result;
 use rmcp::{ServiceExt, transport::stdio};
+use config::Config;
 use github::GitHubService;
+use redact::RedactingStderr;
 use tracing_subscriber::{self, EnvFilter};
 
 /// MCP GitHub CLI Service Server
 /// Communicates with client through standard input/output streams
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Initialize logging
-    tracing_subscriber::fmt()
-        .with_env_filter(EnvFilter::from_default_env().add_directive(tracing::Level::INFO.into()))
-        .with_writer(std::io::stderr)
-        .with_ansi(false)
-        .init();
+    init_logging();
 
     tracing::info!("Starting MCP GitHub server...");
 
     // Create GitHub service instance
-    let service = GitHubService::new().serve(stdio()).await?;
+    let config = Config::from_env();
+    let github = GitHubService::new(&config)?;
+    let service = github.clone().serve(stdio()).await?;
 
-    // Wait for service to stop
+    // Wait for service to stop or a shutdown signal
     tracing::info!("Service started, waiting for requests...");
-    service.waiting().await?;
-    
-    tracing::info!("Service stopped");
+    tokio::select! {
+        quit = service.waiting() => {
+            tracing::info!("Service stopped: {:?}", quit?);
+        }
+        signal = shutdown_signal() => {
+            tracing::info!("Received {}, waiting up to {}s for running tool calls", signal, config.shutdown_grace.as_secs());
+            // Hold the guard until exit so no new tool call starts
+            let drained = github.drain(config.shutdown_grace).await;
+            if drained.is_none() {
+                tracing::warn!("Grace period elapsed, killing remaining gh commands");
+            }
+            tracing::info!("Service stopped");
+        }
+    }
     Ok(())
 }
+
+/// Initialize logging to stderr (stdout carries the MCP protocol).
+/// RUST_LOG sets the filter, defaulting to info; GH_MCP_LOG_FORMAT selects json or pretty output.
+/// rmcp logs every tool request and response in full at info, secret values and tokens included,
+/// so it stays at warn unless RUST_LOG names it explicitly
+fn init_logging() {
+    let directives = std::env::var("RUST_LOG")
+        .ok()
+        .filter(|directives| !directives.trim().is_empty())
+        .unwrap_or_else(|| "info".to_string());
+    let directives = if directives.contains("rmcp") {
+        directives
+    } else {
+        format!("{},rmcp=warn", directives)
+    };
+    let filter = EnvFilter::try_new(&directives).unwrap_or_else(|_| EnvFilter::new("info,rmcp=warn"));
+    let format = std::env::var("GH_MCP_LOG_FORMAT").unwrap_or_default();
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(RedactingStderr)
+        .with_ansi(false);
+    match format.trim() {
+        "json" => builder.json().init(),
+        "pretty" => builder.pretty().init(),
+        _ => builder.init(),
+    }
+    if !matches!(format.trim(), "" | "json" | "pretty") {
+        tracing::warn!("Ignoring invalid value {:?} for GH_MCP_LOG_FORMAT", format);
+    }
+}
+
+/// Resolve with the name of the first shutdown signal received
+async fn shutdown_signal() -> &'static str {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{SignalKind, signal};
+        match signal(SignalKind::terminate()) {
+            Ok(mut sigterm) => tokio::select! {
+                _ = sigterm.recv() => "SIGTERM",
+                _ = tokio::signal::ctrl_c() => "SIGINT",
+            },
+            Err(e) => {
+                tracing::warn!("Failed to install SIGTERM handler: {}", e);
+                let _ = tokio::signal::ctrl_c().await;
+                "SIGINT"
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+        "Ctrl-C"
+    }
+}
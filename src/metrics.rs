@@ -0,0 +1,87 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use serde_json::{json, Value};
+
+/// Weight of the newest sample in the recent latency average is 1/2^RECENT_LATENCY_SHIFT
+const RECENT_LATENCY_SHIFT: u32 = 3;
+
+/// Counters of one tool, updated without locking
+#[derive(Default)]
+struct ToolMetrics {
+    successes: AtomicU64,
+    failures: AtomicU64,
+    total_latency_us: AtomicU64,
+    /// Exponentially weighted moving average of recent latencies
+    recent_latency_us: AtomicU64,
+}
+
+/// In-memory invocation counts and latencies per tool since startup
+pub struct Metrics {
+    started: Instant,
+    tools: RwLock<HashMap<String, Arc<ToolMetrics>>>,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            tools: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Record one finished tool call
+    pub fn record(&self, tool: &str, success: bool, latency: Duration) {
+        let tool = self.tool(tool);
+        let counter = if success { &tool.successes } else { &tool.failures };
+        let first = tool.successes.load(Ordering::Relaxed) + tool.failures.load(Ordering::Relaxed) == 0;
+        counter.fetch_add(1, Ordering::Relaxed);
+
+        let sample = latency.as_micros().min(u64::MAX as u128) as u64;
+        tool.total_latency_us.fetch_add(sample, Ordering::Relaxed);
+        let _ = tool.recent_latency_us.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |recent| {
+            if first {
+                Some(sample)
+            } else {
+                Some(recent - (recent >> RECENT_LATENCY_SHIFT) + (sample >> RECENT_LATENCY_SHIFT))
+            }
+        });
+    }
+
+    /// Counters of every tool called so far, by tool name
+    pub fn snapshot(&self) -> Value {
+        let tools = self.tools.read().unwrap_or_else(|e| e.into_inner());
+        let snapshot: BTreeMap<&str, Value> = tools
+            .iter()
+            .map(|(name, tool)| {
+                let successes = tool.successes.load(Ordering::Relaxed);
+                let failures = tool.failures.load(Ordering::Relaxed);
+                let calls = successes + failures;
+                let total_ms = tool.total_latency_us.load(Ordering::Relaxed) as f64 / 1000.0;
+                let metrics = json!({
+                    "calls": calls,
+                    "successes": successes,
+                    "failures": failures,
+                    "avg_latency_ms": if calls == 0 { 0.0 } else { total_ms / calls as f64 },
+                    "recent_latency_ms": tool.recent_latency_us.load(Ordering::Relaxed) as f64 / 1000.0,
+                });
+                (name.as_str(), metrics)
+            })
+            .collect();
+        json!({
+            "uptime_secs": self.started.elapsed().as_secs(),
+            "tools": snapshot,
+        })
+    }
+
+    /// Counters of a tool, created on its first call
+    fn tool(&self, name: &str) -> Arc<ToolMetrics> {
+        if let Some(tool) = self.tools.read().unwrap_or_else(|e| e.into_inner()).get(name) {
+            return tool.clone();
+        }
+        let mut tools = self.tools.write().unwrap_or_else(|e| e.into_inner());
+        tools.entry(name.to_string()).or_default().clone()
+    }
+}
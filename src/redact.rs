@@ -0,0 +1,107 @@
+use std::io::{self, Write};
+use std::sync::RwLock;
+
+use tracing_subscriber::fmt::MakeWriter;
+
+/// Placeholder written in place of sensitive values
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Prefixes of GitHub token formats
+const TOKEN_PREFIXES: [&str; 6] = ["github_pat_", "ghp_", "gho_", "ghu_", "ghs_", "ghr_"];
+
+/// Secret values handed out by tools, such as the token returned by auth_token, which may
+/// have no recognizable prefix (GH_TOKEN values, GHES tokens, legacy 40-hex PATs)
+static SECRETS: RwLock<Vec<String>> = RwLock::new(Vec::new());
+
+/// Scrub a value from all future log lines, whatever its format
+pub fn register_secret(value: &str) {
+    let value = value.trim();
+    if value.is_empty() {
+        return;
+    }
+    let mut secrets = SECRETS.write().unwrap_or_else(|e| e.into_inner());
+    if !secrets.iter().any(|secret| secret == value) {
+        secrets.push(value.to_string());
+    }
+}
+
+/// Replace registered secrets and anything that looks like a GitHub token with a placeholder
+pub fn redact_tokens(text: &str) -> String {
+    let mut text = text.to_string();
+    for secret in SECRETS.read().unwrap_or_else(|e| e.into_inner()).iter() {
+        if text.contains(secret.as_str()) {
+            text = text.replace(secret.as_str(), REDACTED);
+        }
+    }
+    let mut redacted = String::with_capacity(text.len());
+    let mut rest = text.as_str();
+    while let Some((start, prefix)) = TOKEN_PREFIXES
+        .iter()
+        .filter_map(|prefix| rest.find(prefix).map(|start| (start, *prefix)))
+        .min_by_key(|(start, _)| *start)
+    {
+        redacted.push_str(&rest[..start]);
+        let token = &rest[start + prefix.len()..];
+        let end = token
+            .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+            .unwrap_or(token.len());
+        redacted.push_str(REDACTED);
+        rest = &token[end..];
+    }
+    redacted.push_str(rest);
+    redacted
+}
+
+/// Log writer for stderr that scrubs tokens from every line
+pub struct RedactingStderr;
+
+impl<'a> MakeWriter<'a> for RedactingStderr {
+    type Writer = RedactingWriter;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        RedactingWriter
+    }
+}
+
+/// Writer produced by [`RedactingStderr`]
+pub struct RedactingWriter;
+
+impl Write for RedactingWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let text = String::from_utf8_lossy(buf);
+        io::stderr().write_all(redact_tokens(&text).as_bytes())?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        io::stderr().flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redact_tokens_scrubs_known_prefixes() {
+        let text = "token ghp_abcDEF123456 and github_pat_11AB_cdEF rest";
+        assert_eq!(redact_tokens(text), "token [REDACTED] and [REDACTED] rest");
+    }
+
+    #[test]
+    fn redact_tokens_stops_at_token_end() {
+        assert_eq!(redact_tokens("Authorization: gho_abc123\"}"), "Authorization: [REDACTED]\"}");
+    }
+
+    #[test]
+    fn redact_tokens_keeps_text_without_tokens() {
+        assert_eq!(redact_tokens("gh pr list --repo octo/ghost"), "gh pr list --repo octo/ghost");
+    }
+
+    #[test]
+    fn redact_tokens_scrubs_registered_secrets() {
+        let token = "0123456789abcdef0123456789abcdef01234567";
+        register_secret(&format!("{}\n", token));
+        assert_eq!(redact_tokens(&format!("text: \"{}\"", token)), "text: \"[REDACTED]\"");
+    }
+}